@@ -0,0 +1,149 @@
+// command line tools for working on puzzles outside of the browser.
+//
+//     sudoku minimize [--symmetry NAME] [PUZZLE...]
+//     sudoku print [--unicode] [--pencilmarks] [PUZZLE...]
+//     sudoku booklet [--title TITLE] [--per-page N] [--output FILE] [PUZZLE...]
+//
+// puzzles are 81 characters on one line, with 0 or . for empty cells (see
+// text.rs for everything that's understood). if no
+// puzzles are given on the command line they're read from stdin, one per line.
+// puzzles are printed back out the same way.
+
+use std::{
+    env, fs,
+    io::{self, BufRead},
+    process,
+};
+use sudoku_solver_seed::{
+    minimize_puzzle, BigBox, Booklet, GridStyle, Symmetry, MAX_PUZZLES_PER_PAGE,
+};
+
+const USAGE: &str = "usage:
+    sudoku minimize [--symmetry NAME] [PUZZLE...]
+    sudoku print [--unicode] [--pencilmarks] [PUZZLE...]
+    sudoku booklet [--title TITLE] [--per-page N] [--output FILE] [PUZZLE...]
+
+symmetries: none, rotational180, rotational90, diagonal, horizontal, vertical, dihedral";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("minimize") => minimize(&args[1..]),
+        Some("print") => print(&args[1..]),
+        Some("booklet") => booklet(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        process::exit(1);
+    }
+}
+
+// the puzzles given after the options, or every line of stdin if there aren't any
+fn read_puzzles(args: &[String]) -> Result<Vec<BigBox>, String> {
+    let lines: Vec<String> = if args.is_empty() {
+        io::stdin()
+            .lock()
+            .lines()
+            .collect::<Result<Vec<String>, io::Error>>()
+            .map_err(|e| e.to_string())?
+    } else {
+        args.to_vec()
+    };
+
+    lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.parse::<BigBox>())
+        .collect()
+}
+
+fn minimize(args: &[String]) -> Result<(), String> {
+    let mut symmetry = Symmetry::None;
+    let mut rest = args;
+
+    if rest.first().map(String::as_str) == Some("--symmetry") {
+        let name = rest.get(1).ok_or_else(|| USAGE.to_string())?;
+        symmetry = name.parse()?;
+        rest = &rest[2..];
+    }
+
+    for board in read_puzzles(rest)? {
+        match minimize_puzzle(&board, symmetry) {
+            Some(minimal) => println!("{}", minimal),
+            None => {
+                return Err(format!(
+                    "puzzle doesn't have exactly one solution: {}",
+                    board
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// draw puzzles as grids, with --pencilmarks every empty cell shows its notes
+fn print(args: &[String]) -> Result<(), String> {
+    let mut style = GridStyle::Ascii;
+    let mut pencilmarks = false;
+    let mut rest = args;
+
+    while let Some(option) = rest.first() {
+        match option.as_str() {
+            "--unicode" => style = GridStyle::Unicode,
+            "--pencilmarks" => pencilmarks = true,
+            _ => break,
+        }
+        rest = &rest[1..];
+    }
+
+    for mut board in read_puzzles(rest)? {
+        if pencilmarks {
+            board.mark_impossible(board.scan_for_make_impossible());
+            println!("{}", board.to_pencilmark_grid(style));
+        } else {
+            println!("{}", board.to_grid(style));
+        }
+    }
+
+    Ok(())
+}
+
+// a pdf of the puzzles to print, with the answers at the back. it's written to
+// booklet.pdf unless --output says otherwise
+fn booklet(args: &[String]) -> Result<(), String> {
+    let mut booklet = Booklet::new("Sudoku");
+    let mut output = "booklet.pdf".to_string();
+    let mut rest = args;
+
+    while let Some(option) = rest.first() {
+        let value = || rest.get(1).ok_or_else(|| USAGE.to_string());
+        match option.as_str() {
+            "--title" => booklet.title = value()?.clone(),
+            "--per-page" => {
+                booklet.puzzles_per_page = value()?
+                    .parse()
+                    .ok()
+                    .filter(|per_page| (1..=MAX_PUZZLES_PER_PAGE).contains(per_page))
+                    .ok_or_else(|| {
+                        format!(
+                            "--per-page needs a number from 1 to {}",
+                            MAX_PUZZLES_PER_PAGE
+                        )
+                    })?
+            }
+            "--output" => output = value()?.clone(),
+            _ => break,
+        }
+        rest = &rest[2..];
+    }
+
+    for (number, board) in read_puzzles(rest)?.into_iter().enumerate() {
+        booklet.add_puzzle(&format!("Puzzle {}", number + 1), board);
+    }
+
+    fs::write(&output, booklet.to_pdf()).map_err(|e| format!("couldn't write {}: {}", output, e))
+}
@@ -0,0 +1,617 @@
+// printable booklets of puzzles as pdf files. the puzzles come first, a few to
+// a page with a title and how hard they are, then an answer key with the
+// solutions drawn smaller.
+//
+// the pdf is written by hand so it works anywhere the solver does: pages are
+// A4, everything is lines and text in the standard Helvetica fonts that every
+// pdf reader has built in, so nothing needs to be embedded.
+
+use crate::killer::{cage_label_cell, cage_outline};
+use crate::lines::{arrow_shape, cell_center};
+use crate::{rate_puzzle, unique_solution_with, BigBox, Layout, LineKind, Marker, Rating};
+
+// A4 in points
+const PAGE_WIDTH: f64 = 595.0;
+const PAGE_HEIGHT: f64 = 842.0;
+const PAGE_MARGIN: f64 = 40.0;
+// room at the top of each page for the booklet's title
+const HEADER_HEIGHT: f64 = 30.0;
+// room above each grid for its title
+const LABEL_HEIGHT: f64 = 24.0;
+// any more and the grids get too small to write in, 12 to a page is 3 by 4
+// grids a bit under 150 points across
+pub const MAX_PUZZLES_PER_PAGE: usize = 12;
+const ANSWERS_PER_PAGE: usize = MAX_PUZZLES_PER_PAGE;
+
+#[derive(Debug, Clone)]
+pub struct BookletPuzzle {
+    pub title: String,
+    pub board: BigBox,
+    pub rating: Rating,
+}
+
+#[derive(Debug, Clone)]
+pub struct Booklet {
+    pub title: String,
+    pub puzzles_per_page: usize,
+    pub puzzles: Vec<BookletPuzzle>,
+}
+
+impl Booklet {
+    // an empty booklet, 4 puzzles to a page
+    pub fn new(title: &str) -> Booklet {
+        Booklet {
+            title: title.to_string(),
+            puzzles_per_page: 4,
+            puzzles: Vec::new(),
+        }
+    }
+
+    // add a puzzle, rating it for its difficulty label
+    pub fn add_puzzle(&mut self, title: &str, board: BigBox) {
+        let rating = rate_puzzle(&board);
+        self.puzzles.push(BookletPuzzle {
+            title: title.to_string(),
+            board,
+            rating,
+        });
+    }
+
+    // the puzzles `puzzles_per_page` to a page, kept between 1 and
+    // MAX_PUZZLES_PER_PAGE
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut pages: Vec<String> = Vec::new();
+        let per_page = self.puzzles_per_page.clamp(1, MAX_PUZZLES_PER_PAGE);
+
+        for (page_number, puzzles) in self.puzzles.chunks(per_page).enumerate() {
+            let mut page = page_header(&self.title, pages.len() + 1);
+            for (slot, puzzle) in page_slots(per_page).iter().zip(puzzles) {
+                let number = page_number * per_page + slot.position + 1;
+                let label = format!("{}. {} - {}", number, puzzle.title, puzzle.rating);
+                let board = &puzzle.board;
+                page.push_str(&slot.draw(
+                    board.layout(),
+                    &label,
+                    &board.get_values(),
+                    &board.get_givens(),
+                ));
+            }
+            pages.push(page);
+        }
+
+        let answers_title = format!("{} - answers", self.title);
+        for (page_number, puzzles) in self.puzzles.chunks(ANSWERS_PER_PAGE).enumerate() {
+            let mut page = page_header(&answers_title, pages.len() + 1);
+            for (slot, puzzle) in page_slots(ANSWERS_PER_PAGE).iter().zip(puzzles) {
+                let number = page_number * ANSWERS_PER_PAGE + slot.position + 1;
+                let layout = puzzle.board.layout();
+                let givens = puzzle.board.get_givens();
+                let label = format!("{}. {}", number, puzzle.title);
+                match unique_solution_with(layout, &givens) {
+                    Some(solution) => page.push_str(&slot.draw(layout, &label, &solution, &givens)),
+                    None => {
+                        page.push_str(&slot.draw(layout, &label, &givens, &givens));
+                        page.push_str(&text(
+                            slot.x,
+                            slot.y - 12.0,
+                            9.0,
+                            false,
+                            "doesn't have exactly one solution",
+                        ));
+                    }
+                }
+            }
+            pages.push(page);
+        }
+
+        // an empty booklet still needs a page
+        if pages.is_empty() {
+            pages.push(page_header(&self.title, 1));
+        }
+        write_pdf(&pages)
+    }
+}
+
+// where one grid goes on a page, `position` is its place on the page counting
+// from the top left. x and y are the bottom left corner of the grid
+struct Slot {
+    position: usize,
+    x: f64,
+    y: f64,
+    size: f64,
+}
+
+// spread `count` grids over a page in as many columns as fit nicely
+fn page_slots(count: usize) -> Vec<Slot> {
+    let cols = match count {
+        0..=2 => 1,
+        3..=6 => 2,
+        _ => 3,
+    };
+    let rows = (count + cols - 1) / cols;
+
+    let slot_width = (PAGE_WIDTH - PAGE_MARGIN * 2.0) / cols as f64;
+    let slot_height = (PAGE_HEIGHT - PAGE_MARGIN * 2.0 - HEADER_HEIGHT) / rows as f64;
+    let size = (slot_width * 0.9).min(slot_height - LABEL_HEIGHT * 1.5);
+
+    (0..count)
+        .map(|position| {
+            let (row, col) = (position / cols, position % cols);
+            let left = PAGE_MARGIN + col as f64 * slot_width;
+            let top = PAGE_HEIGHT - PAGE_MARGIN - HEADER_HEIGHT - row as f64 * slot_height;
+            Slot {
+                position,
+                x: left + (slot_width - size) / 2.0,
+                y: top - LABEL_HEIGHT - size,
+                size,
+            }
+        })
+        .collect()
+}
+
+impl Slot {
+    // the grid with its label above it. givens are bold, any other numbers
+    // (the rest of a solution) are regular
+    fn draw(&self, layout: &Layout, label: &str, values: &[i32], givens: &[i32]) -> String {
+        let cells_across = layout.size();
+        let cell = self.size / cells_across as f64;
+        let line = |(x1, y1): (f64, f64), (x2, y2): (f64, f64), width: f64| {
+            format!(
+                "{:.2} w {:.2} {:.2} m {:.2} {:.2} l S\n",
+                width, x1, y1, x2, y2
+            )
+        };
+        let mut content = String::new();
+
+        // a windoku's windows are shaded, under everything else
+        for index in layout.windows().concat() {
+            let x = self.x + layout.col(index) as f64 * cell;
+            let y = self.y + (cells_across - 1 - layout.row(index)) as f64 * cell;
+            content.push_str(&format!(
+                "0.88 g {:.2} {:.2} {:.2} {:.2} re f 0 g\n",
+                x, y, cell, cell
+            ));
+        }
+
+        // an x-sudoku's diagonals in grey
+        if layout.has_diagonals() {
+            content.push_str("0.6 G\n");
+            content.push_str(&line(
+                (self.x, self.y),
+                (self.x + self.size, self.y + self.size),
+                self.size / 300.0,
+            ));
+            content.push_str(&line(
+                (self.x, self.y + self.size),
+                (self.x + self.size, self.y),
+                self.size / 300.0,
+            ));
+            content.push_str("0 G\n");
+        }
+
+        // thermometers in light grey with a bulb at the start, arrows in grey
+        // from a circle, and whispers in green
+        if !layout.lines().is_empty() {
+            // pdf y goes up the page
+            let at =
+                |(x, y): (f64, f64)| (self.x + x * cell, self.y + (cells_across as f64 - y) * cell);
+            content.push_str("1 J 1 j\n");
+            for line in layout.lines() {
+                let mut points: Vec<(f64, f64)> = line
+                    .cells
+                    .iter()
+                    .map(|index| at(cell_center(layout, *index)))
+                    .collect();
+                let (color, width) = match line.kind {
+                    LineKind::Thermometer => ("0.75 G 0.75 g", cell * 0.25),
+                    LineKind::Arrow => ("0.6 G", cell / 25.0),
+                    LineKind::Whisper => ("0.34 0.89 0.54 RG", cell * 0.12),
+                };
+                content.push_str(&format!("{} {:.2} w\n", color, width));
+
+                if line.kind == LineKind::Thermometer {
+                    content.push_str(&format!(
+                        "{} f\n",
+                        circle(points[0].0, points[0].1, cell * 0.35)
+                    ));
+                }
+                if line.kind == LineKind::Arrow {
+                    content.push_str(&format!(
+                        "{} S\n",
+                        circle(points[0].0, points[0].1, cell * 0.4)
+                    ));
+                    let (shaft, head) = arrow_shape(layout, line, 0.4);
+                    points = shaft.into_iter().map(at).collect();
+                    let (x1, y1) = points[points.len() - 1];
+                    for (x2, y2) in head.iter().map(|end| at(*end)) {
+                        content
+                            .push_str(&format!("{:.2} {:.2} m {:.2} {:.2} l S\n", x1, y1, x2, y2));
+                    }
+                }
+                let mut path = format!("{:.2} {:.2} m", points[0].0, points[0].1);
+                for (x, y) in &points[1..] {
+                    path.push_str(&format!(" {:.2} {:.2} l", x, y));
+                }
+                content.push_str(&format!("{} S\n", path));
+            }
+            content.push_str("0 G 0 g 0 J 0 j\n");
+        }
+
+        // thin lines between the cells, then thick ones along the edges of the
+        // boxes (or jigsaw regions)
+        for offset in (0..=cells_across).map(|line| line as f64 * cell) {
+            let thin = self.size / 500.0;
+            content.push_str(&line(
+                (self.x + offset, self.y),
+                (self.x + offset, self.y + self.size),
+                thin,
+            ));
+            content.push_str(&line(
+                (self.x, self.y + offset),
+                (self.x + self.size, self.y + offset),
+                thin,
+            ));
+        }
+        // the square caps close up the corners where thick lines meet
+        content.push_str("2 J\n");
+        for index in 0..layout.cell_count() {
+            // pdf y goes up the page, so this is the bottom left corner of the cell
+            let x = self.x + layout.col(index) as f64 * cell;
+            let y = self.y + (cells_across - 1 - layout.row(index)) as f64 * cell;
+            let [top, right, bottom, left] = layout.box_edges(index);
+            // edges inside the grid belong to two cells, so each cell draws its
+            // right and bottom edges, and top and left ones only along the outside
+            let edges = [
+                (
+                    top && layout.row(index) == 0,
+                    (x, y + cell),
+                    (x + cell, y + cell),
+                ),
+                (right, (x + cell, y), (x + cell, y + cell)),
+                (bottom, (x, y), (x + cell, y)),
+                (left && layout.col(index) == 0, (x, y), (x, y + cell)),
+            ];
+            for (_, from, to) in edges.iter().filter(|(thick, _, _)| *thick) {
+                content.push_str(&line(*from, *to, self.size / 120.0));
+            }
+        }
+        content.push_str("0 J\n");
+
+        // a killer's cages, dashed just inside the cell edges, with their sums
+        // in the top left corner
+        if !layout.cages().is_empty() {
+            content.push_str(&format!("[{:.2} {:.2}] 0 d\n", cell / 12.0, cell / 18.0));
+            for index in 0..layout.cell_count() {
+                let x = self.x + layout.col(index) as f64 * cell;
+                let top = self.y + (cells_across - layout.row(index)) as f64 * cell;
+                for ((x1, y1), (x2, y2)) in cage_outline(layout, index, 0.08) {
+                    content.push_str(&line(
+                        (x + x1 * cell, top - y1 * cell),
+                        (x + x2 * cell, top - y2 * cell),
+                        cell / 60.0,
+                    ));
+                }
+            }
+            content.push_str("[] 0 d\n");
+        }
+        for cage in layout.cages() {
+            if let Some(index) = cage_label_cell(cage) {
+                let x = self.x + layout.col(index) as f64 * cell;
+                let top = self.y + (cells_across - layout.row(index)) as f64 * cell;
+                content.push_str(&text(
+                    x + cell * 0.1,
+                    top - cell * 0.3,
+                    cell * 0.18,
+                    false,
+                    &cage.sum.to_string(),
+                ));
+            }
+        }
+
+        // kropki dots, Xs and Vs on the middle of the line between their cells
+        let center = |index: i32| {
+            (
+                self.x + (layout.col(index) as f64 + 0.5) * cell,
+                self.y + (cells_across as f64 - layout.row(index) as f64 - 0.5) * cell,
+            )
+        };
+        for (a, b, marker) in layout.markers() {
+            let ((x1, y1), (x2, y2)) = (center(*a), center(*b));
+            let (x, y) = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+            let letter = match marker {
+                Marker::WhiteDot => {
+                    content.push_str(&format!(
+                        "1 g {:.2} w {} b 0 g\n",
+                        cell / 60.0,
+                        circle(x, y, cell * 0.12)
+                    ));
+                    continue;
+                }
+                Marker::BlackDot => {
+                    content.push_str(&format!("{} f\n", circle(x, y, cell * 0.12)));
+                    continue;
+                }
+                Marker::X => "X",
+                Marker::V => "V",
+            };
+            let size = cell * 0.3;
+            content.push_str(&format!(
+                "1 g {:.2} {:.2} {:.2} {:.2} re f 0 g\n",
+                x - cell * 0.14,
+                y - cell * 0.14,
+                cell * 0.28,
+                cell * 0.28
+            ));
+            // X and V in Helvetica bold are 0.667 of the font size wide
+            content.push_str(&text(
+                x - size * 0.667 / 2.0,
+                y - size * 0.35,
+                size,
+                true,
+                letter,
+            ));
+        }
+
+        let font_size = cell * 0.6;
+        for (index, value) in values.iter().enumerate() {
+            let symbol = match Layout::symbol(*value) {
+                Some(symbol) if layout.is_number(*value) => symbol,
+                _ => continue,
+            };
+            // the digits in Helvetica are 0.556 of the font size wide, letters
+            // are a little wider, and they're about 0.7 tall
+            let symbol_width = if *value > 9 { 0.667 } else { 0.556 };
+            let (row, col) = (layout.row(index as i32), layout.col(index as i32));
+            let x = self.x + col as f64 * cell + (cell - font_size * symbol_width) / 2.0;
+            let y =
+                self.y + (cells_across - 1 - row) as f64 * cell + (cell - font_size * 0.7) / 2.0;
+            content.push_str(&text(
+                x,
+                y,
+                font_size,
+                givens[index] != 0,
+                &symbol.to_string(),
+            ));
+        }
+
+        let label_size = (LABEL_HEIGHT * 0.5).min(cell * 0.5).max(7.0);
+        content.push_str(&text(
+            self.x,
+            self.y + self.size + label_size * 0.6,
+            label_size,
+            false,
+            label,
+        ));
+        content
+    }
+}
+
+// the booklet's title at the top of the page and the page number at the bottom
+fn page_header(title: &str, page_number: usize) -> String {
+    let mut content = text(
+        PAGE_MARGIN,
+        PAGE_HEIGHT - PAGE_MARGIN - 12.0,
+        16.0,
+        true,
+        title,
+    );
+    let number = page_number.to_string();
+    content.push_str(&text(
+        (PAGE_WIDTH - number.len() as f64 * 10.0 * 0.556) / 2.0,
+        PAGE_MARGIN / 2.0,
+        10.0,
+        false,
+        &number,
+    ));
+    content
+}
+
+// one line of text with its baseline starting at x, y
+fn text(x: f64, y: f64, size: f64, bold: bool, string: &str) -> String {
+    format!(
+        "BT /{} {:.2} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+        if bold { "F2" } else { "F1" },
+        size,
+        x,
+        y,
+        escape(string)
+    )
+}
+
+// a circle as four curves, ready to be filled or stroked
+fn circle(x: f64, y: f64, r: f64) -> String {
+    // how far the control points stick out to make a quarter circle
+    let k = r * 0.5523;
+    let quarters = [
+        [(r, k), (k, r), (0.0, r)],
+        [(-k, r), (-r, k), (-r, 0.0)],
+        [(-r, -k), (-k, -r), (0.0, -r)],
+        [(k, -r), (r, -k), (r, 0.0)],
+    ];
+    let mut path = format!("{:.2} {:.2} m", x + r, y);
+    for quarter in quarters.iter() {
+        for (dx, dy) in quarter.iter() {
+            path.push_str(&format!(" {:.2} {:.2}", x + dx, y + dy));
+        }
+        path.push_str(" c");
+    }
+    path
+}
+
+// pdf strings need their brackets and backslashes escaped. the fonts use the
+// windows latin 1 encoding, anything that isn't in it becomes ?
+fn escape(string: &str) -> String {
+    let mut escaped = String::new();
+    for c in string.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if (c as u32) < 32 => escaped.push(' '),
+            c if (c as u32) < 256 => escaped.push(c),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+// put the pages' drawing commands into a pdf file. the objects are the
+// catalog, the page list, the two fonts, then a page and its contents for
+// every page
+fn write_pdf(pages: &[String]) -> Vec<u8> {
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+    let page_ids: Vec<usize> = (0..pages.len()).map(|page| 5 + page * 2).collect();
+
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objects.push(
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{} 0 R", id))
+                .collect::<Vec<String>>()
+                .join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+    );
+    for font in ["Helvetica", "Helvetica-Bold"].iter() {
+        objects.push(
+            format!(
+                "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                font
+            )
+            .into_bytes(),
+        );
+    }
+
+    for (page, id) in pages.iter().zip(&page_ids) {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                id + 1
+            )
+            .into_bytes(),
+        );
+
+        let stream = latin_1(page);
+        let mut contents = format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes();
+        contents.extend(stream);
+        contents.extend(b"\nendstream");
+        objects.push(contents);
+    }
+
+    let mut pdf: Vec<u8> = b"%PDF-1.4\n".to_vec();
+    let mut offsets: Vec<usize> = Vec::new();
+    for (number, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", number + 1).into_bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+    }
+    pdf.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .into_bytes(),
+    );
+    pdf
+}
+
+// the escaped text is all below 256, one byte per character
+fn latin_1(string: &str) -> Vec<u8> {
+    string.chars().map(|c| c as u32 as u8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::PUZZLE;
+
+    // find where some text first shows up in the pdf
+    fn position(pdf: &[u8], text: &str) -> Option<usize> {
+        pdf.windows(text.len())
+            .position(|window| window == text.as_bytes())
+    }
+
+    #[test]
+    fn pages_for_the_puzzles_and_the_answers() {
+        let mut booklet = Booklet::new("Weekend (easy)");
+        for number in 0..5 {
+            booklet.add_puzzle(&format!("Puzzle {}", number + 1), PUZZLE.parse().unwrap());
+        }
+        let pdf = booklet.to_pdf();
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+
+        // two pages of puzzles, one of answers
+        assert!(position(&pdf, "/Count 3 >>").is_some());
+        assert!(position(&pdf, "(Weekend \\(easy\\) - answers)").is_some());
+        assert!(position(&pdf, "(5. Puzzle 5 - Easy \\(1.5\\))").is_some());
+    }
+
+    #[test]
+    fn the_xref_points_at_the_objects() {
+        let mut booklet = Booklet::new("Booklet");
+        booklet.add_puzzle("Only one", PUZZLE.parse().unwrap());
+        let pdf = booklet.to_pdf();
+        let text = String::from_utf8_lossy(&pdf);
+
+        let startxref: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .unwrap()
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(text[startxref..].starts_with("xref\n0 "));
+
+        let offsets: Vec<usize> = text[startxref..]
+            .lines()
+            .skip(3)
+            .take_while(|line| line.ends_with(" n "))
+            .map(|line| line[..10].parse().unwrap())
+            .collect();
+        assert_eq!(offsets.len(), 4 + 2 * 2);
+        for (number, offset) in offsets.iter().enumerate() {
+            assert!(text[*offset..].starts_with(&format!("{} 0 obj\n", number + 1)));
+        }
+    }
+
+    #[test]
+    fn too_many_to_a_page_are_spread_out() {
+        let mut booklet = Booklet::new("Booklet");
+        booklet.puzzles_per_page = 100;
+        for number in 0..13 {
+            booklet.add_puzzle(&format!("Puzzle {}", number + 1), PUZZLE.parse().unwrap());
+        }
+        let pdf = booklet.to_pdf();
+
+        // two pages of puzzles and two of answers
+        assert!(position(&pdf, "/Count 4 >>").is_some());
+        for slot in page_slots(MAX_PUZZLES_PER_PAGE) {
+            assert!(slot.size > 100.0);
+            assert!(slot.y > PAGE_MARGIN / 2.0);
+        }
+    }
+
+    #[test]
+    fn empty_booklets_still_have_a_page() {
+        let pdf = Booklet::new("Nothing yet").to_pdf();
+        assert!(position(&pdf, "/Count 1 >>").is_some());
+        assert_eq!(escape("(a\\b) \u{2603}"), "\\(a\\\\b\\) ?");
+    }
+}
@@ -0,0 +1,148 @@
+// canonical (minlex) form of a puzzle. two puzzles are the same puzzle in
+// disguise if one can be turned into the other by relabeling the numbers,
+// swapping rows inside a band, swapping columns inside a stack, swapping whole
+// bands or stacks, and transposing. the minlex form is the smallest 81 number
+// string (0 for empty cells) out of every one of those disguises, so two
+// puzzles are equivalent exactly when their minlex forms are the same.
+
+use crate::{BigBox, Layout};
+
+const PERMUTATIONS_OF_3: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+// every order the 9 rows (or columns) can be put in without breaking up the
+// bands (or stacks), 6 band orders times 6 orders for each band's rows
+fn line_orders() -> Vec<[usize; 9]> {
+    let mut orders: Vec<[usize; 9]> = Vec::new();
+    for bands in PERMUTATIONS_OF_3.iter() {
+        for first in PERMUTATIONS_OF_3.iter() {
+            for second in PERMUTATIONS_OF_3.iter() {
+                for third in PERMUTATIONS_OF_3.iter() {
+                    let mut order = [0; 9];
+                    for (band_position, lines) in [first, second, third].iter().enumerate() {
+                        for (line_position, line) in lines.iter().enumerate() {
+                            order[band_position * 3 + line_position] =
+                                bands[band_position] * 3 + line;
+                        }
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+// if the puzzle with these rows and columns relabeled is smaller than `best`,
+// write it into `best`. the numbers are relabeled in the order they show up, which
+// is the smallest way to label any one layout
+fn try_layout(values: &[i32], rows: &[usize; 9], cols: &[usize; 9], best: &mut [i32; 81]) {
+    let mut labels = [0; 10];
+    let mut next_label = 1;
+    let mut candidate = [0; 81];
+    let mut smaller = false;
+
+    for position in 0..81 {
+        let value = values[rows[position / 9] * 9 + cols[position % 9]] as usize;
+        let label = if value == 0 {
+            0
+        } else {
+            if labels[value] == 0 {
+                labels[value] = next_label;
+                next_label += 1;
+            }
+            labels[value]
+        };
+
+        // stop as soon as this layout can't beat the best one
+        if !smaller {
+            if label > best[position] {
+                return;
+            }
+            smaller = label < best[position];
+        }
+        candidate[position] = label;
+    }
+
+    if smaller {
+        *best = candidate;
+    }
+}
+
+// the minlex form of the values in a board (candidates don't matter). only
+// 9x9 boards have one, any other size comes back as it is
+pub fn canonical_form(board: &BigBox) -> BigBox {
+    if board.layout() != &Layout::standard() {
+        return board.clone();
+    }
+    let values = board.get_values();
+    let transposed: Vec<i32> = (0..81).map(|i| values[(i % 9) * 9 + i / 9]).collect();
+    let orders = line_orders();
+
+    // bigger than any real layout, so the first one tried always wins
+    let mut best = [10; 81];
+    for grid in [&values, &transposed].iter() {
+        for rows in &orders {
+            for cols in &orders {
+                try_layout(grid, rows, cols, &mut best);
+            }
+        }
+    }
+
+    // best always has 81 cells
+    BigBox::new(best.to_vec()).unwrap()
+}
+
+// true if the two puzzles are the same puzzle under some disguise
+pub fn is_equivalent(a: &BigBox, b: &BigBox) -> bool {
+    if a.layout() != b.layout() {
+        return false;
+    }
+    canonical_form(a).get_values() == canonical_form(b).get_values()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::PUZZLE;
+
+    #[test]
+    fn disguises_have_the_same_minlex_form() {
+        let board: BigBox = PUZZLE.parse().unwrap();
+        let disguised = board
+            .relabeled(&[9, 8, 7, 6, 5, 4, 3, 2, 1])
+            .and_then(|board| board.with_rows_swapped(3, 5))
+            .and_then(|board| board.with_stacks_swapped(0, 2))
+            .unwrap()
+            .transposed();
+        assert_ne!(disguised.get_values(), board.get_values());
+        assert!(is_equivalent(&board, &disguised));
+
+        // the minlex form is its own minlex form, and starts with the smallest numbers
+        let canonical = canonical_form(&board);
+        assert_eq!(
+            canonical_form(&canonical).get_values(),
+            canonical.get_values()
+        );
+        assert!(canonical.get_values() <= board.get_values());
+    }
+
+    #[test]
+    fn different_puzzles_are_not_equivalent() {
+        let board: BigBox = PUZZLE.parse().unwrap();
+        let mut values = board.get_values();
+        values[2] = 0;
+        assert!(!is_equivalent(&board, &BigBox::new(values).unwrap()));
+
+        // other sizes don't get changed
+        let small: BigBox = "1.34.412214.43.1".parse().unwrap();
+        assert_eq!(canonical_form(&small).get_values(), small.get_values());
+        assert!(!is_equivalent(&board, &small));
+    }
+}
@@ -0,0 +1,320 @@
+// puzzle files from desktop sudoku programs.
+//
+// Simple Sudoku (.ss) draws the puzzle as a grid:
+//
+//     *-----------*
+//     |..3|.2.|6..|
+//     |9..|3.5|..1|
+//     |..1|8.6|4..|
+//     |---+---+---|
+//     ...
+//     *-----------*
+//
+// SadMan Sudoku (.sdk) has `#` header lines (#A author, #D description, ...),
+// then the puzzle as 9 lines of 9. if the puzzle is being played there's a
+// [Puzzle] section with the clues and a [State] section with everything that's
+// been filled in so far.
+//
+// SadMan multi puzzle files (.sdm) have one puzzle per line, 81 numbers with 0
+// for empty cells.
+//
+// only .sdk can tell clues apart from numbers placed while playing, so the other
+// two only read and write the clues. all three are 9x9 only, so the writers
+// return an error for any other size.
+
+use crate::text::{check_nine_by_nine, parse_nine_by_nine};
+use crate::BigBox;
+
+// a .sdk puzzle, with its header lines
+#[derive(Debug, Clone)]
+pub struct SdkPuzzle {
+    pub board: BigBox,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub comment: Option<String>,
+    pub date: Option<String>,
+    pub source: Option<String>,
+    pub level: Option<String>,
+    pub url: Option<String>,
+    // header lines with codes this doesn't know about, kept so they're written back
+    pub other_headers: Vec<(char, String)>,
+}
+
+impl SdkPuzzle {
+    // a puzzle with no headers
+    pub fn new(board: BigBox) -> SdkPuzzle {
+        SdkPuzzle {
+            board,
+            author: None,
+            description: None,
+            comment: None,
+            date: None,
+            source: None,
+            level: None,
+            url: None,
+            other_headers: Vec::new(),
+        }
+    }
+
+    fn header_mut(&mut self, code: char) -> Option<&mut Option<String>> {
+        match code {
+            'A' => Some(&mut self.author),
+            'D' => Some(&mut self.description),
+            'C' => Some(&mut self.comment),
+            'B' => Some(&mut self.date),
+            'S' => Some(&mut self.source),
+            'L' => Some(&mut self.level),
+            'U' => Some(&mut self.url),
+            _ => None,
+        }
+    }
+
+    fn headers(&self) -> Vec<(char, &String)> {
+        let known = [
+            ('A', &self.author),
+            ('D', &self.description),
+            ('C', &self.comment),
+            ('B', &self.date),
+            ('S', &self.source),
+            ('L', &self.level),
+            ('U', &self.url),
+        ];
+
+        known
+            .iter()
+            .filter_map(|(code, value)| value.as_ref().map(|value| (*code, value)))
+            .chain(
+                self.other_headers
+                    .iter()
+                    .map(|(code, value)| (*code, value)),
+            )
+            .collect()
+    }
+}
+
+// parse 81 cells with the tolerant parser in text.rs, as plain values
+fn parse_values(text: &str) -> Result<Vec<i32>, String> {
+    parse_nine_by_nine(text).map(|board| board.get_values())
+}
+
+// a board with the clues from `givens` and everything else in `state` placed
+// on top of them
+fn board_with_state(givens: &[i32], state: &[i32]) -> Result<BigBox, String> {
+    let mut board = BigBox::new(givens.to_vec()).ok_or("couldn't make a board")?;
+    for (index, (given, value)) in givens.iter().zip(state).enumerate() {
+        if *given == 0 && *value != 0 {
+            board.little_boxes[index].update_value(*value);
+        } else if *given != 0 && *value != *given {
+            return Err(format!("the state changes the clue in cell {}", index));
+        }
+    }
+    Ok(board)
+}
+
+// 9 lines of 9, `.` for empty cells
+fn nine_by_nine(values: &[i32]) -> String {
+    values
+        .chunks(9)
+        .map(|row| {
+            let mut line: String = row
+                .iter()
+                .map(|value| match value {
+                    1..=9 => value.to_string(),
+                    _ => ".".to_string(),
+                })
+                .collect();
+            line.push('\n');
+            line
+        })
+        .collect()
+}
+
+pub fn read_ss(text: &str) -> Result<BigBox, String> {
+    // the *-----------* lines have * in them, which the text parser reads as
+    // empty cells, so leave them out
+    let grid: String = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('*'))
+        .collect::<Vec<&str>>()
+        .join("\n");
+    parse_nine_by_nine(&grid)
+}
+
+pub fn write_ss(board: &BigBox) -> Result<String, String> {
+    check_nine_by_nine(board)?;
+    let givens = board.get_givens();
+    let mut text = String::from("*-----------*\n");
+
+    for (row, values) in givens.chunks(9).enumerate() {
+        if row == 3 || row == 6 {
+            text.push_str("|---+---+---|\n");
+        }
+        for (col, value) in values.iter().enumerate() {
+            if col % 3 == 0 {
+                text.push('|');
+            }
+            match value {
+                1..=9 => text.push_str(&value.to_string()),
+                _ => text.push('.'),
+            }
+        }
+        text.push_str("|\n");
+    }
+
+    text.push_str("*-----------*\n");
+    Ok(text)
+}
+
+pub fn read_sdk(text: &str) -> Result<SdkPuzzle, String> {
+    let mut headers: Vec<(char, String)> = Vec::new();
+    let mut puzzle = String::new();
+    let mut state = String::new();
+    let mut in_state = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix('#') {
+            let mut chars = header.chars();
+            if let Some(code) = chars.next() {
+                headers.push((code, chars.as_str().trim().to_string()));
+            }
+        } else if trimmed.eq_ignore_ascii_case("[puzzle]") {
+            in_state = false;
+        } else if trimmed.eq_ignore_ascii_case("[state]") {
+            in_state = true;
+        } else if in_state {
+            state.push_str(trimmed);
+        } else {
+            puzzle.push_str(trimmed);
+        }
+    }
+
+    let givens = parse_values(&puzzle)?;
+    let board = if state.is_empty() {
+        BigBox::new(givens).ok_or("couldn't make a board")?
+    } else {
+        board_with_state(&givens, &parse_values(&state)?)?
+    };
+
+    let mut sdk_puzzle = SdkPuzzle::new(board);
+    for (code, value) in headers {
+        match sdk_puzzle.header_mut(code) {
+            Some(header) => *header = Some(value),
+            None => sdk_puzzle.other_headers.push((code, value)),
+        }
+    }
+    Ok(sdk_puzzle)
+}
+
+pub fn write_sdk(sdk_puzzle: &SdkPuzzle) -> Result<String, String> {
+    check_nine_by_nine(&sdk_puzzle.board)?;
+    let mut text = String::new();
+    for (code, value) in sdk_puzzle.headers() {
+        text.push_str(&format!("#{}{}\n", code, value));
+    }
+
+    let givens = sdk_puzzle.board.get_givens();
+    let values = sdk_puzzle.board.get_values();
+    if givens == values {
+        text.push_str(&nine_by_nine(&givens));
+    } else {
+        text.push_str("[Puzzle]\n");
+        text.push_str(&nine_by_nine(&givens));
+        text.push_str("[State]\n");
+        text.push_str(&nine_by_nine(&values));
+    }
+
+    Ok(text)
+}
+
+pub fn read_sdm(text: &str) -> Result<Vec<BigBox>, String> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_nine_by_nine)
+        .collect()
+}
+
+pub fn write_sdm(boards: &[BigBox]) -> Result<String, String> {
+    boards
+        .iter()
+        .map(|board| {
+            check_nine_by_nine(board)?;
+            let mut line: String = board
+                .get_givens()
+                .iter()
+                .map(|value| value.to_string())
+                .collect();
+            line.push('\n');
+            Ok(line)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SS: &str = "*-----------*
+|..3|.2.|6..|
+|9..|3.5|..1|
+|..1|8.6|4..|
+|---+---+---|
+|..8|1.2|9..|
+|7..|...|..8|
+|..6|7.8|2..|
+|---+---+---|
+|..2|6.9|5..|
+|8..|2.3|..9|
+|..5|.1.|3..|
+*-----------*
+";
+
+    fn four_by_four() -> BigBox {
+        "1.3..4.1.1..3.2.".parse().unwrap()
+    }
+
+    #[test]
+    fn ss_round_trip() {
+        let board = read_ss(SS).unwrap();
+        assert_eq!(board.get_givens()[2], 3);
+        assert_eq!(write_ss(&board).unwrap(), SS);
+    }
+
+    #[test]
+    fn sdk_round_trip_keeps_headers_and_state() {
+        let mut board = read_ss(SS).unwrap();
+        board.update_little_box_value(&0, &4);
+        let mut sdk_puzzle = SdkPuzzle::new(board);
+        sdk_puzzle.author = Some("someone".to_string());
+        sdk_puzzle
+            .other_headers
+            .push(('Z', "something else".to_string()));
+
+        let text = write_sdk(&sdk_puzzle).unwrap();
+        assert!(text.contains("[State]"));
+        let read = read_sdk(&text).unwrap();
+        assert_eq!(read.board.get_values(), sdk_puzzle.board.get_values());
+        assert_eq!(read.board.get_givens(), sdk_puzzle.board.get_givens());
+        assert_eq!(read.author.as_deref(), Some("someone"));
+        assert_eq!(write_sdk(&read).unwrap(), text);
+    }
+
+    #[test]
+    fn sdm_round_trip() {
+        let board = read_ss(SS).unwrap();
+        let text = write_sdm(&[board.clone(), board.clone()]).unwrap();
+        let read = read_sdm(&text).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[1].get_values(), board.get_values());
+    }
+
+    #[test]
+    fn other_sizes_are_errors() {
+        let board = four_by_four();
+        assert!(write_ss(&board).is_err());
+        assert!(write_sdk(&SdkPuzzle::new(board.clone())).is_err());
+        assert!(write_sdm(&[read_ss(SS).unwrap(), board]).is_err());
+        assert!(read_ss("1.3..4.1.1..3.2.").is_err());
+        assert!(read_sdm("1.3..4.1.1..3.2.\n").is_err());
+    }
+}
@@ -0,0 +1,229 @@
+// a game in progress, everything needed to save it and pick it back up later.
+// the board has the clues, the numbers filled in so far and the notes, on top
+// of that there's every step that's been applied and how long it's been played.
+//
+// with the `serde` feature on this (and the board, steps and ratings) can be
+// turned into json and back, for saving games on a server or sending boards
+// between the browser and a backend.
+
+use crate::{BigBox, Step};
+#[cfg(feature = "serde")]
+use crate::{Layout, LittleBox};
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameState {
+    pub board: BigBox,
+    // the steps applied to the board, oldest first
+    pub solve_log: Vec<Step>,
+    // time spent playing, in milliseconds
+    pub elapsed_ms: u64,
+}
+
+impl GameState {
+    // a game that hasn't been started
+    pub fn new(board: BigBox) -> GameState {
+        GameState {
+            board,
+            solve_log: Vec::new(),
+            elapsed_ms: 0,
+        }
+    }
+
+    // apply a step to the board and keep it in the log if it changed anything
+    pub fn apply_step(&mut self, step: Step) -> bool {
+        let changed = self.board.apply_step(&step);
+        if changed {
+            self.solve_log.push(step);
+        }
+        changed
+    }
+
+    // solve as far as the standard techniques go, logging every step
+    pub fn solve(&mut self) {
+        let steps = self.board.solve_puzzle();
+        self.solve_log.extend(steps);
+    }
+
+    pub fn add_time(&mut self, ms: u64) {
+        self.elapsed_ms += ms;
+    }
+}
+
+// a cell and a board as they come in from json. boards get sent from
+// anywhere, so they're checked before they're used: every cell has to fit the
+// board, with no notes or numbers past its size, and there has to be a cell
+// for every place on the board
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+pub(crate) struct SavedLittleBox {
+    value: Option<i32>,
+    index: i32,
+    given: bool,
+    size: i32,
+    possibles: u32,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SavedLittleBox> for LittleBox {
+    type Error = String;
+
+    fn try_from(saved: SavedLittleBox) -> Result<LittleBox, String> {
+        if Layout::for_size(saved.size).is_none() {
+            return Err(format!(
+                "cell {} is for a {1}x{1} board, there's no such board",
+                saved.index, saved.size
+            ));
+        }
+        if let Some(value) = saved.value {
+            if !(0..=saved.size).contains(&value) {
+                return Err(format!(
+                    "cell {} has {}, it only goes up to {}",
+                    saved.index, value, saved.size
+                ));
+            }
+        }
+        if saved.possibles & !LittleBox::all_possibles(saved.size) != 0 {
+            return Err(format!(
+                "cell {} has notes past {}",
+                saved.index, saved.size
+            ));
+        }
+        Ok(LittleBox {
+            value: saved.value,
+            index: saved.index,
+            given: saved.given,
+            size: saved.size,
+            possibles: saved.possibles,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+pub(crate) struct SavedBigBox {
+    little_boxes: Vec<LittleBox>,
+    times_updated: u32,
+    layout: Layout,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SavedBigBox> for BigBox {
+    type Error = String;
+
+    fn try_from(saved: SavedBigBox) -> Result<BigBox, String> {
+        let layout = saved.layout;
+        if saved.little_boxes.len() != layout.cell_count() as usize {
+            return Err(format!(
+                "the board has {} cells, a {}x{} board has {}",
+                saved.little_boxes.len(),
+                layout.size(),
+                layout.size(),
+                layout.cell_count()
+            ));
+        }
+        for (index, little_box) in saved.little_boxes.iter().enumerate() {
+            if little_box.index != index as i32 {
+                return Err(format!(
+                    "cell {} says it's cell {}",
+                    index, little_box.index
+                ));
+            }
+            if little_box.size != layout.size() {
+                return Err(format!(
+                    "cell {} is for a {1}x{1} board, not {2}x{2}",
+                    index,
+                    little_box.size,
+                    layout.size()
+                ));
+            }
+        }
+        Ok(BigBox {
+            little_boxes: saved.little_boxes,
+            times_updated: saved.times_updated,
+            layout,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::PUZZLE;
+    use crate::{HiddenSingle, Technique};
+
+    #[test]
+    fn only_steps_that_change_something_are_logged() {
+        let mut board: BigBox = PUZZLE.parse().unwrap();
+        board.mark_impossible(board.scan_for_make_impossible());
+        let mut game = GameState::new(board);
+
+        let step = HiddenSingle.find(&game.board).unwrap();
+        assert!(game.apply_step(step.clone()));
+        assert!(!game.apply_step(step));
+        assert_eq!(game.solve_log.len(), 1);
+    }
+
+    #[test]
+    fn solving_logs_every_step() {
+        let mut game = GameState::new(PUZZLE.parse().unwrap());
+        game.add_time(1500);
+        game.add_time(500);
+        game.solve();
+        assert!(game.board.is_solved());
+        assert_eq!(game.solve_log.len(), PUZZLE.matches('.').count());
+        assert_eq!(game.elapsed_ms, 2000);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let mut board: BigBox = PUZZLE.parse().unwrap();
+        board.mark_impossible(board.scan_for_make_impossible());
+        let mut game = GameState::new(board);
+        game.apply_step(HiddenSingle.find(&game.board).unwrap());
+        game.add_time(90_000);
+
+        let json = serde_json::to_string(&game).unwrap();
+        let read: GameState = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.board.to_string(), game.board.to_string());
+        assert_eq!(read.board.layout(), game.board.layout());
+        assert_eq!(read.solve_log, game.solve_log);
+        assert_eq!(read.elapsed_ms, 90_000);
+        for (read, written) in read.board.little_boxes.iter().zip(&game.board.little_boxes) {
+            assert_eq!(read.get_possibles(), written.get_possibles());
+            assert_eq!(read.is_given(), written.is_given());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_that_doesnt_fit_is_an_error() {
+        let board: BigBox = PUZZLE.parse().unwrap();
+        let json = serde_json::to_value(GameState::new(board)).unwrap();
+        let read = |change: &dyn Fn(&mut serde_json::Value)| {
+            let mut json = json.clone();
+            change(&mut json);
+            serde_json::from_value::<GameState>(json)
+        };
+
+        assert!(read(&|_| {}).is_ok());
+        assert!(read(&|json| {
+            json["board"]["little_boxes"].as_array_mut().unwrap().pop();
+        })
+        .is_err());
+        assert!(read(&|json| json["board"]["little_boxes"][0]["size"] = 16.into()).is_err());
+        assert!(read(&|json| json["board"]["little_boxes"][0]["index"] = 5.into()).is_err());
+        assert!(read(&|json| json["board"]["little_boxes"][0]["value"] = 10.into()).is_err());
+        assert!(
+            read(&|json| json["board"]["little_boxes"][0]["possibles"] = (1 << 10).into()).is_err()
+        );
+        assert!(read(&|json| json["board"]["layout"]["box_width"] = 0.into()).is_err());
+        assert!(read(&|json| json["board"]["layout"]["regions"] = vec![0; 81].into()).is_err());
+        assert!(read(&|json| json["board"]["layout"]["cages"] =
+            serde_json::json!([{"sum": 3, "cells": [80, 81]}]))
+        .is_err());
+    }
+}
@@ -0,0 +1,718 @@
+// makes new puzzles. a random full board is made first, then clues are taken
+// away one at a time as long as the puzzle still has exactly one solution. the
+// puzzle that's left is rated once, and if it isn't as hard as what was asked
+// for it starts over with a new board.
+//
+// the brute force solver in here is only used to check for unique solutions,
+// the real solving is still done with the techniques.
+//
+// everything is driven by a seed, so the same seed and difficulty always make
+// the same puzzle (that's how everyone gets the same puzzle of the day).
+//
+// puzzles are only generated at the usual 9x9, the difficulty ratings are made
+// for that size. counting solutions and minimizing work on any board.
+
+use crate::relations::related_to;
+use crate::{rate_puzzle, BigBox, Difficulty, Layout, LineKind, Random, Rating, Symmetry};
+
+// how many full boards to try before giving up on getting the right difficulty.
+// this runs when the page's button is clicked, so it can't go on for long
+const MAX_ATTEMPTS: u32 = 10;
+
+// easy puzzles stop taking clues away once they're down to this many, with that
+// many clues they nearly always only need singles. the others go as low as they
+// can, rating every puzzle along the way would be far too slow
+const EASY_CLUES: usize = 32;
+
+// backtracking solver that keeps a bit mask of the used numbers for every row,
+// column and medium box, and any other units the layout has. a killer's cages
+// get a mask too, and a running total to check the sums. cells a chess move
+// apart, and cells with a rule between them, are checked one by one, and so
+// are the sums along arrows
+struct BruteForce {
+    size: i32,
+    grid: Vec<i32>,
+    // the row, column and medium box of every cell
+    units: Vec<(usize, usize, usize)>,
+    rows: Vec<u32>,
+    cols: Vec<u32>,
+    boxes: Vec<u32>,
+    // the extra units (like the diagonals) every cell is in, cages count as
+    // units here too since they can't repeat numbers either
+    extra_units: Vec<Vec<usize>>,
+    extras: Vec<u32>,
+    // the sum of every cage, what's been filled in so far and how many cells
+    // are still empty
+    cages: Vec<(i32, i32, i32)>,
+    cage_of: Vec<Option<usize>>,
+    // which of the extra units is each cage's
+    cage_units: Vec<usize>,
+    // the cells a knight's or king's move away, on boards with those rules
+    chess_peers: Vec<Vec<usize>>,
+    // every set of numbers that adds up to each cage's sum, as bit masks.
+    // None when there are too many to bother with
+    combinations: Vec<Option<Vec<u32>>>,
+    // the cells each cell has a rule with (like non-consecutive), and for
+    // every number the other cell can have, the numbers that don't go with it
+    // (nothing for an empty cell)
+    relations: Vec<Vec<(usize, Vec<u32>)>>,
+    // the numbers each cell can never have because of those rules, like a 5
+    // on a whisper or a 9 in a thermometer's bulb
+    ruled_out: Vec<u32>,
+    // every arrow's circle and the cells along it, and the arrows each cell
+    // is on
+    arrows: Vec<(usize, Vec<usize>)>,
+    arrows_of: Vec<Vec<usize>>,
+}
+
+impl BruteForce {
+    // None if the values given already break the rules
+    fn new(layout: &Layout, values: &[i32]) -> Option<BruteForce> {
+        if values.len() != layout.cell_count() as usize {
+            return None;
+        }
+
+        let size = layout.size();
+        let mut brute_force = BruteForce {
+            size,
+            grid: vec![0; values.len()],
+            units: (0..layout.cell_count())
+                .map(|index| {
+                    (
+                        layout.row(index) as usize,
+                        layout.col(index) as usize,
+                        layout.box_number(index) as usize,
+                    )
+                })
+                .collect(),
+            rows: vec![0; size as usize],
+            cols: vec![0; size as usize],
+            boxes: vec![0; size as usize],
+            extra_units: vec![Vec::new(); values.len()],
+            extras: vec![0; layout.extra_units().len() + layout.cages().len()],
+            cages: layout
+                .cages()
+                .iter()
+                .map(|cage| (cage.sum, 0, cage.cells.len() as i32))
+                .collect(),
+            cage_of: vec![None; values.len()],
+            cage_units: (0..layout.cages().len())
+                .map(|cage| layout.extra_units().len() + cage)
+                .collect(),
+            combinations: layout
+                .cages()
+                .iter()
+                .map(|cage| sum_combinations(size, cage.cells.len() as i32, cage.sum))
+                .collect(),
+            chess_peers: (0..layout.cell_count())
+                .map(|index| {
+                    layout
+                        .chess_peers(index)
+                        .iter()
+                        .map(|peer| *peer as usize)
+                        .collect()
+                })
+                .collect(),
+            relations: (0..layout.cell_count())
+                .map(|index| {
+                    related_to(layout, index)
+                        .into_iter()
+                        .map(|(other, relation)| {
+                            let wrong = (0..=size)
+                                .map(|other_number| {
+                                    (1..=size)
+                                        .filter(|number| {
+                                            other_number != 0
+                                                && !relation.allows(*number, other_number)
+                                        })
+                                        .fold(0, |wrong, number| wrong | 1 << number)
+                                })
+                                .collect();
+                            (other as usize, wrong)
+                        })
+                        .collect()
+                })
+                .collect(),
+            ruled_out: vec![0; values.len()],
+            arrows: layout
+                .lines()
+                .iter()
+                .filter(|line| line.kind == LineKind::Arrow)
+                .map(|line| {
+                    let cells: Vec<usize> = line.cells.iter().map(|cell| *cell as usize).collect();
+                    (cells[0], cells[1..].to_vec())
+                })
+                .collect(),
+            arrows_of: vec![Vec::new(); values.len()],
+        };
+        for (arrow, (circle, cells)) in brute_force.arrows.iter().enumerate() {
+            for cell in std::iter::once(circle).chain(cells) {
+                brute_force.arrows_of[*cell].push(arrow);
+            }
+        }
+        let cage_units = layout.cages().iter().map(|cage| cage.cells.clone());
+        for (unit, cells) in layout
+            .extra_units()
+            .into_iter()
+            .chain(cage_units)
+            .enumerate()
+        {
+            for cell in cells {
+                brute_force.extra_units[cell as usize].push(unit);
+            }
+        }
+        for (cage, cells) in layout.cages().iter().map(|cage| &cage.cells).enumerate() {
+            for cell in cells {
+                brute_force.cage_of[*cell as usize] = Some(cage);
+            }
+        }
+
+        for (index, value) in values.iter().enumerate() {
+            if layout.is_number(*value) {
+                if brute_force.used(index) & (1 << value) != 0 {
+                    return None;
+                }
+                brute_force.set(index, *value);
+            }
+        }
+        brute_force.rule_out();
+
+        Some(brute_force)
+    }
+
+    // works out ruled_out. a number stays in a cell only while every cell it
+    // has a rule with still has a number that goes with it, which is checked
+    // over and over until nothing else goes
+    fn rule_out(&mut self) {
+        let all = ((1 << (self.size + 1)) - 1) & !1;
+        let mut options: Vec<u32> = self
+            .grid
+            .iter()
+            .map(|value| if *value == 0 { all } else { 1 << value })
+            .collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in 0..self.grid.len() {
+                for (other, wrong) in &self.relations[index] {
+                    let kept = (1..=self.size)
+                        .filter(|other_number| options[*other] & (1 << other_number) != 0)
+                        .fold(0, |kept, other_number| kept | !wrong[other_number as usize])
+                        & options[index];
+                    if kept != options[index] {
+                        options[index] = kept;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        self.ruled_out = options.iter().map(|options| all & !options).collect();
+    }
+
+    fn used(&self, index: usize) -> u32 {
+        let (row, col, med_box) = self.units[index];
+        let used = self.extra_units[index].iter().fold(
+            self.rows[row] | self.cols[col] | self.boxes[med_box] | self.ruled_out[index],
+            |used, unit| used | self.extras[*unit],
+        );
+        // an empty peer is 0, which isn't a number anyway
+        let used = self.chess_peers[index]
+            .iter()
+            .fold(used, |used, peer| used | 1 << self.grid[*peer]);
+        let used = self.relations[index]
+            .iter()
+            .fold(used, |used, (other, wrong)| {
+                used | wrong[self.grid[*other] as usize]
+            });
+        let used = self.arrows_of[index].iter().fold(used, |used, arrow| {
+            used | self.wrong_for_arrow(*arrow, index)
+        });
+        match self.cage_of[index] {
+            Some(cage) => used | self.wrong_for_sum(cage),
+            None => used,
+        }
+    }
+
+    // the numbers that can't go in one of a cage's empty cells, because there
+    // isn't a way to reach the sum with them and the numbers already in it.
+    // without a list of the ways to make the sum, only the smallest and
+    // biggest the cage can still add up to are checked
+    fn wrong_for_sum(&self, cage: usize) -> u32 {
+        let (sum, filled, empty) = self.cages[cage];
+        let used = self.extras[self.cage_units[cage]];
+        let all = ((1 << (self.size + 1)) - 1) & !1;
+        if let Some(combinations) = &self.combinations[cage] {
+            let possible = combinations
+                .iter()
+                .filter(|combination| *combination & used == used)
+                .fold(0, |possible, combination| possible | combination);
+            return all & !possible;
+        }
+
+        let left = sum - filled;
+        let others = empty - 1;
+        let smallest = others * (others + 1) / 2;
+        let biggest = others * (2 * self.size - others + 1) / 2;
+        (1..=self.size)
+            .filter(|number| number + smallest > left || number + biggest < left)
+            .fold(0, |wrong, number| wrong | 1 << number)
+    }
+
+    // the numbers that can't go in a cell on an arrow, or in its circle,
+    // without making the sum impossible. every number along the arrow is at
+    // least 1
+    fn wrong_for_arrow(&self, arrow: usize, index: usize) -> u32 {
+        let (circle, cells) = &self.arrows[arrow];
+        let others = cells.iter().filter(|cell| **cell != index);
+        let filled: i32 = others.clone().map(|cell| self.grid[*cell]).sum();
+        let empty = others.filter(|cell| self.grid[**cell] == 0).count() as i32;
+
+        (1..=self.size)
+            .filter(|number| {
+                if index == *circle {
+                    *number < filled + empty || (empty == 0 && *number != filled)
+                } else {
+                    match self.grid[*circle] {
+                        0 => filled + number + empty > self.size,
+                        sum => {
+                            filled + number + empty > sum || (empty == 0 && filled + number != sum)
+                        }
+                    }
+                }
+            })
+            .fold(0, |wrong, number| wrong | 1 << number)
+    }
+
+    fn set(&mut self, index: usize, value: i32) {
+        let (row, col, med_box) = self.units[index];
+        self.grid[index] = value;
+        self.rows[row] |= 1 << value;
+        self.cols[col] |= 1 << value;
+        self.boxes[med_box] |= 1 << value;
+        for unit in &self.extra_units[index] {
+            self.extras[*unit] |= 1 << value;
+        }
+        if let Some(cage) = self.cage_of[index] {
+            self.cages[cage].1 += value;
+            self.cages[cage].2 -= 1;
+        }
+    }
+
+    fn unset(&mut self, index: usize) {
+        let (row, col, med_box) = self.units[index];
+        let value = self.grid[index];
+        self.grid[index] = 0;
+        self.rows[row] &= !(1 << value);
+        self.cols[col] &= !(1 << value);
+        self.boxes[med_box] &= !(1 << value);
+        for unit in &self.extra_units[index] {
+            self.extras[*unit] &= !(1 << value);
+        }
+        if let Some(cage) = self.cage_of[index] {
+            self.cages[cage].1 -= value;
+            self.cages[cage].2 += 1;
+        }
+    }
+
+    // counts solutions up to `limit`. the first solution found is left in
+    // `solution`. with a Random the numbers are tried in a random order
+    fn search(
+        &mut self,
+        limit: usize,
+        count: &mut usize,
+        solution: &mut Option<Vec<i32>>,
+        mut random: Option<&mut Random>,
+    ) {
+        // find the empty cell with the fewest options
+        let mut best: Option<(usize, u32)> = None;
+        for index in 0..self.grid.len() {
+            if self.grid[index] == 0 {
+                let options = self.size as u32 - (self.used(index) >> 1).count_ones();
+                match best {
+                    Some((_, best_options)) if best_options <= options => (),
+                    _ => best = Some((index, options)),
+                }
+            }
+        }
+
+        let index = match best {
+            Some((_, 0)) => return,
+            Some((index, _)) => index,
+            None => {
+                *count += 1;
+                if solution.is_none() {
+                    *solution = Some(self.grid.clone());
+                }
+                return;
+            }
+        };
+
+        let mut numbers: Vec<i32> = (1..=self.size)
+            .filter(|n| self.used(index) & (1 << n) == 0)
+            .collect();
+        if let Some(random) = random.as_deref_mut() {
+            random.shuffle(&mut numbers);
+        }
+
+        for number in numbers {
+            self.set(index, number);
+            self.search(limit, count, solution, random.as_deref_mut());
+            self.unset(index);
+            if *count >= limit {
+                return;
+            }
+        }
+    }
+}
+
+// more ways to make a cage's sum than this and the brute force only checks
+// that the sum can still be reached
+const MAX_COMBINATIONS: usize = 4096;
+
+// the sets of `count` different numbers up to `size` that add up to `sum`, as
+// bit masks. None if there are more than MAX_COMBINATIONS of them
+fn sum_combinations(size: i32, count: i32, sum: i32) -> Option<Vec<u32>> {
+    fn add(size: i32, from: i32, count: i32, sum: i32, mask: u32, found: &mut Vec<u32>) -> bool {
+        if count == 0 {
+            if sum == 0 {
+                found.push(mask);
+            }
+            return found.len() <= MAX_COMBINATIONS;
+        }
+        // stop once the smallest numbers left are already too big
+        for number in
+            (from..=size).take_while(|number| number * count + count * (count - 1) / 2 <= sum)
+        {
+            if !add(
+                size,
+                number + 1,
+                count - 1,
+                sum - number,
+                mask | 1 << number,
+                found,
+            ) {
+                return false;
+            }
+        }
+        true
+    }
+
+    let mut found = Vec::new();
+    if add(size, 1, count, sum, 0, &mut found) {
+        Some(found)
+    } else {
+        None
+    }
+}
+
+// count the solutions of a puzzle (0 is empty), but stop counting at `limit`.
+// the board size comes from how many values there are
+pub fn count_solutions(values: &[i32], limit: usize) -> usize {
+    match Layout::for_cell_count(values.len()) {
+        Some(layout) => count_solutions_with(&layout, values, limit),
+        None => 0,
+    }
+}
+
+// count solutions on a board with a layout of your own
+pub fn count_solutions_with(layout: &Layout, values: &[i32], limit: usize) -> usize {
+    let mut count = 0;
+    if let Some(mut brute_force) = BruteForce::new(layout, values) {
+        brute_force.search(limit, &mut count, &mut None, None);
+    }
+    count
+}
+
+// the solution of a puzzle, if it has exactly one
+pub fn unique_solution(values: &[i32]) -> Option<Vec<i32>> {
+    unique_solution_with(&Layout::for_cell_count(values.len())?, values)
+}
+
+pub fn unique_solution_with(layout: &Layout, values: &[i32]) -> Option<Vec<i32>> {
+    let mut brute_force = BruteForce::new(layout, values)?;
+    let mut count = 0;
+    let mut solution = None;
+    brute_force.search(2, &mut count, &mut solution, None);
+
+    if count == 1 {
+        solution
+    } else {
+        None
+    }
+}
+
+// a random, completely filled in board
+pub fn random_full_board(random: &mut Random) -> Vec<i32> {
+    let mut brute_force = BruteForce::new(&Layout::standard(), &[0; 81]).unwrap();
+    let mut count = 0;
+    let mut solution = None;
+    brute_force.search(1, &mut count, &mut solution, Some(random));
+
+    // an empty board always has a solution
+    solution.unwrap()
+}
+
+fn rate_values(values: &[i32]) -> Option<Rating> {
+    BigBox::new(values.to_vec()).map(|board| rate_puzzle(&board))
+}
+
+// take clues away from a full board, skipping any clue that would make the
+// puzzle have more than one solution. the clues of a symmetry orbit are taken
+// away together. only an easy puzzle stops early, see EASY_CLUES
+fn remove_clues(
+    full_board: &[i32],
+    difficulty: Difficulty,
+    symmetry: Symmetry,
+    random: &mut Random,
+) -> Vec<i32> {
+    let mut values = full_board.to_vec();
+    let mut orbits = symmetry.orbits();
+    random.shuffle(&mut orbits);
+    let clues_left = if difficulty == Difficulty::Easy {
+        EASY_CLUES
+    } else {
+        0
+    };
+
+    for orbit in orbits {
+        if values.iter().filter(|value| **value != 0).count() <= clues_left {
+            break;
+        }
+
+        let clues: Vec<i32> = orbit.iter().map(|index| values[*index as usize]).collect();
+        for index in &orbit {
+            values[*index as usize] = 0;
+        }
+
+        if count_solutions(&values, 2) != 1 {
+            for (index, clue) in orbit.iter().zip(clues) {
+                values[*index as usize] = clue;
+            }
+        }
+    }
+
+    values
+}
+
+// take away every clue that isn't needed for the puzzle to have one solution,
+// so every clue left is needed. the clues of a symmetry orbit are only taken
+// away together, so a symmetric puzzle stays symmetric (use Symmetry::None to
+// not care). None if the puzzle doesn't have exactly one solution to begin with
+pub fn minimize_puzzle(board: &BigBox, symmetry: Symmetry) -> Option<BigBox> {
+    let layout = board.layout();
+    let mut values = board.get_values();
+    if count_solutions_with(layout, &values, 2) != 1 {
+        return None;
+    }
+
+    for orbit in symmetry.orbits_in(layout.size()) {
+        let clues: Vec<i32> = orbit.iter().map(|index| values[*index as usize]).collect();
+        if clues.iter().all(|clue| *clue == 0) {
+            continue;
+        }
+
+        for index in &orbit {
+            values[*index as usize] = 0;
+        }
+        if count_solutions_with(layout, &values, 2) != 1 {
+            for (index, clue) in orbit.iter().zip(clues) {
+                values[*index as usize] = clue;
+            }
+        }
+    }
+
+    BigBox::with_layout(layout.clone(), values)
+}
+
+// a puzzle made by the generator, along with what's needed to make it again
+#[derive(Debug, Clone)]
+pub struct GeneratedPuzzle {
+    pub board: BigBox,
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub symmetry: Symmetry,
+    pub rating: Rating,
+}
+
+// make a puzzle with one solution that rates as `difficulty`. None if it
+// couldn't make one after MAX_ATTEMPTS tries. that happens a lot for Expert,
+// since only the hardest techniques rate that high, so not every seed gives a
+// puzzle at every difficulty (see generate_puzzle_from)
+pub fn generate_puzzle(difficulty: Difficulty, seed: u64) -> Option<GeneratedPuzzle> {
+    generate_symmetric_puzzle(difficulty, Symmetry::None, seed)
+}
+
+// the puzzle for the first seed from `seed` up that gives one, so there's
+// always a puzzle. the seed in it is the one that worked, generate_puzzle gives
+// the same puzzle back for it
+pub fn generate_puzzle_from(difficulty: Difficulty, seed: u64) -> GeneratedPuzzle {
+    let mut seed = seed;
+    loop {
+        if let Some(puzzle) = generate_puzzle(difficulty, seed) {
+            return puzzle;
+        }
+        seed = seed.wrapping_add(1);
+    }
+}
+
+// same as generate_puzzle, but the clues are laid out with `symmetry`. the
+// stricter symmetries leave fewer ways to take clues away, so they can need
+// more tries to hit the harder difficulties
+pub fn generate_symmetric_puzzle(
+    difficulty: Difficulty,
+    symmetry: Symmetry,
+    seed: u64,
+) -> Option<GeneratedPuzzle> {
+    let mut random = Random::new(seed);
+
+    for _ in 0..MAX_ATTEMPTS {
+        let full_board = random_full_board(&mut random);
+        let values = remove_clues(&full_board, difficulty, symmetry, &mut random);
+
+        let rating = match rate_values(&values) {
+            Some(rating) => rating,
+            None => continue,
+        };
+        if rating.difficulty() == Some(difficulty) {
+            return Some(GeneratedPuzzle {
+                board: BigBox::new(values)?,
+                seed,
+                difficulty,
+                symmetry,
+                rating,
+            });
+        }
+    }
+
+    None
+}
+
+// the seed for a date, it's just the date written out (2021-04-18 is 20210418)
+// so it's easy to tell which day a seed is for
+pub fn daily_seed(year: i32, month: u32, day: u32) -> u64 {
+    (year as u64) * 10_000 + (month as u64) * 100 + day as u64
+}
+
+// the puzzle of the day, the same one for everybody on that date. when the
+// date's seed doesn't give a puzzle the seeds after it are tried, so every day
+// has one at every difficulty. its seed can be a little past the date's
+pub fn daily_puzzle(year: i32, month: u32, day: u32, difficulty: Difficulty) -> GeneratedPuzzle {
+    generate_puzzle_from(difficulty, daily_seed(year, month, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::PUZZLE;
+    use crate::Cage;
+
+    fn puzzle_values() -> Vec<i32> {
+        PUZZLE.parse::<BigBox>().unwrap().get_values()
+    }
+
+    #[test]
+    fn generated_puzzles_have_one_solution() {
+        let puzzle = generate_puzzle(Difficulty::Easy, 2021).unwrap();
+        let values = puzzle.board.get_values();
+        assert_eq!(count_solutions(&values, 2), 1);
+        assert_eq!(puzzle.rating.difficulty(), Some(Difficulty::Easy));
+
+        // the same seed makes the same puzzle
+        let again = generate_puzzle(Difficulty::Easy, 2021).unwrap();
+        assert_eq!(again.board.get_values(), values);
+    }
+
+    #[test]
+    fn symmetric_puzzles_follow_the_symmetry() {
+        let puzzle =
+            generate_symmetric_puzzle(Difficulty::Easy, Symmetry::Rotational180, 2021).unwrap();
+        let values = puzzle.board.get_values();
+        assert!(Symmetry::Rotational180.is_followed_by(&values));
+        assert_eq!(count_solutions(&values, 2), 1);
+    }
+
+    #[test]
+    fn minimized_puzzles_need_every_clue() {
+        let minimized = minimize_puzzle(&PUZZLE.parse().unwrap(), Symmetry::None).unwrap();
+        let values = minimized.get_values();
+        assert_eq!(count_solutions(&values, 2), 1);
+
+        for index in (0..81).filter(|index| values[*index] != 0) {
+            let mut fewer = values.clone();
+            fewer[index] = 0;
+            assert_eq!(count_solutions(&fewer, 2), 2);
+        }
+
+        assert!(minimize_puzzle(&BigBox::new(vec![0; 81]).unwrap(), Symmetry::None).is_none());
+    }
+
+    #[test]
+    fn counting_stops_at_the_limit() {
+        assert_eq!(count_solutions(&[0; 81], 2), 2);
+        assert_eq!(count_solutions(&[0; 81], 5), 5);
+
+        let solution = unique_solution(&puzzle_values()).unwrap();
+        assert!(solution.iter().all(|value| (1..=9).contains(value)));
+        assert_eq!(count_solutions(&solution, 2), 1);
+
+        // two 1s in the first row
+        let mut broken = solution.clone();
+        broken.swap(0, 9);
+        assert_eq!(count_solutions(&broken, 2), 0);
+        assert!(unique_solution(&[0; 81]).is_none());
+
+        // a 4x4 has a layout of its own
+        let small: BigBox = "1.34.412214.43.1".parse().unwrap();
+        assert_eq!(count_solutions(&small.get_values(), 2), 1);
+    }
+
+    #[test]
+    fn variant_rules_are_checked() {
+        let solution = unique_solution(&puzzle_values()).unwrap();
+        let sum = solution[0] + solution[1];
+
+        let layout = Layout::standard()
+            .with_cages(vec![Cage::new(sum, vec![0, 1])])
+            .unwrap();
+        assert_eq!(count_solutions_with(&layout, &solution, 2), 1);
+        let layout = Layout::standard()
+            .with_cages(vec![Cage::new(sum + 1, vec![0, 1])])
+            .unwrap();
+        assert_eq!(count_solutions_with(&layout, &solution, 2), 0);
+
+        // an empty board still has solutions with the extra rules
+        let layout = Layout::standard().with_diagonals().with_windows().unwrap();
+        let mut count = 0;
+        let mut found = None;
+        BruteForce::new(&layout, &[0; 81])
+            .unwrap()
+            .search(1, &mut count, &mut found, None);
+        let found = found.unwrap();
+        assert_eq!(count_solutions_with(&layout, &found, 2), 1);
+        assert!(layout.extra_units().iter().all(|unit| {
+            let mut numbers: Vec<i32> = unit.iter().map(|index| found[*index as usize]).collect();
+            numbers.sort_unstable();
+            numbers == (1..=9).collect::<Vec<i32>>()
+        }));
+    }
+
+    #[test]
+    fn daily_seeds_are_the_date() {
+        assert_eq!(daily_seed(2021, 4, 18), 20210418);
+    }
+
+    #[test]
+    fn every_day_has_a_puzzle() {
+        for day in 18..21 {
+            for difficulty in Difficulty::ALL.iter() {
+                let puzzle = daily_puzzle(2021, 4, day, *difficulty);
+                assert_eq!(puzzle.rating.difficulty(), Some(*difficulty));
+                assert!(puzzle.seed >= daily_seed(2021, 4, day));
+
+                // the seed that worked gives the same puzzle on its own
+                let again = generate_puzzle(*difficulty, puzzle.seed).unwrap();
+                assert_eq!(again.board.get_values(), puzzle.board.get_values());
+            }
+        }
+    }
+}
@@ -0,0 +1,509 @@
+// killer sudoku. groups of cells called cages are drawn on the board with a
+// sum in the corner: the numbers in a cage add up to its sum and can't repeat.
+// most killers don't have any clues at all, the sums are enough.
+//
+// the cages live in the layout (see Layout::with_cages), so the cells in a
+// cage are peers and the usual techniques already know they can't repeat. the
+// techniques in here use the sums:
+//
+// - cage combinations: only some sets of numbers add up to a cage's sum, so
+//   notes that can't be part of any of them go
+// - innies and outies: a row, column or box always adds up to 45 (on a 9x9),
+//   so taking away the cages inside it leaves the sum of the few cells that
+//   aren't in them. the cells of the cages that stick out of it work the same
+//   way
+// - cage splitting: when some of a cage's cells are one of those innie or
+//   outie groups, the rest of the cage adds up to whatever is left of its sum
+//
+// cages are written one to a line, the sum and then the cells, like
+// `15: r1c1 r1c2 r2c1`.
+
+use crate::{BigBox, Layout, Step, Technique};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cage {
+    pub sum: i32,
+    pub cells: Vec<i32>,
+}
+
+impl Cage {
+    pub fn new(sum: i32, cells: Vec<i32>) -> Cage {
+        Cage { sum, cells }
+    }
+}
+
+// innie and outie groups bigger than this aren't worth checking, there are too
+// many ways for that many cells to add up to anything
+const MAX_SUM_GROUP: usize = 5;
+
+// read cages written one to a line as `sum: r1c1 r1c2 ...`, rows and columns
+// count from 1. blank lines are skipped
+pub fn parse_cages(layout: &Layout, text: &str) -> Result<Vec<Cage>, String> {
+    let mut cages: Vec<Cage> = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let mut parts = line.splitn(2, ':');
+        let sum = parts
+            .next()
+            .and_then(|sum| sum.trim().parse::<i32>().ok())
+            .ok_or_else(|| format!("cage doesn't start with its sum: {}", line))?;
+        let cells = parts
+            .next()
+            .ok_or_else(|| format!("cage doesn't have a : after its sum: {}", line))?
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|cell| !cell.is_empty())
+            .map(|cell| parse_cell(layout, cell))
+            .collect::<Result<Vec<i32>, String>>()?;
+        cages.push(Cage::new(sum, cells));
+    }
+    Ok(cages)
+}
+
+// the cages as text, the way parse_cages reads them
+pub fn write_cages(layout: &Layout) -> String {
+    layout
+        .cages()
+        .iter()
+        .map(|cage| {
+            let cells: Vec<String> = cage
+                .cells
+                .iter()
+                .map(|cell| format!("r{}c{}", layout.row(*cell) + 1, layout.col(*cell) + 1))
+                .collect();
+            format!("{}: {}\n", cage.sum, cells.join(" "))
+        })
+        .collect()
+}
+
+// a cell written as r1c1
+pub(crate) fn parse_cell(layout: &Layout, cell: &str) -> Result<i32, String> {
+    let lower = cell.to_lowercase();
+    let position = lower.strip_prefix('r').and_then(|rest| {
+        let mut numbers = rest.splitn(2, 'c');
+        let row = numbers.next()?.parse::<i32>().ok()?;
+        let col = numbers.next()?.parse::<i32>().ok()?;
+        Some((row, col))
+    });
+
+    match position {
+        Some((row, col)) if layout.is_number(row) && layout.is_number(col) => {
+            Ok((row - 1) * layout.size() + col - 1)
+        }
+        _ => Err(format!("{} isn't a cell, they look like r1c1", cell)),
+    }
+}
+
+// the dashed line around a cage, drawn a little inside the cell edges, as
+// lines measured in cells from the top left corner of the cell. nothing for a
+// cell that isn't in a cage
+pub(crate) fn cage_outline(
+    layout: &Layout,
+    index: i32,
+    inset: f64,
+) -> Vec<((f64, f64), (f64, f64))> {
+    let cage = match layout.cage_of(index) {
+        Some(cage) => cage,
+        None => return Vec::new(),
+    };
+    let (row, col, size) = (layout.row(index), layout.col(index), layout.size());
+    // is the cell this far away in the same cage
+    let joined = |down: i32, across: i32| {
+        let (row, col) = (row + down, col + across);
+        (0..size).contains(&row)
+            && (0..size).contains(&col)
+            && cage.cells.contains(&(row * size + col))
+    };
+
+    let (near, far) = (inset, 1.0 - inset);
+    let (top, right, bottom, left) = (!joined(-1, 0), !joined(0, 1), !joined(1, 0), !joined(0, -1));
+    // the lines run to the edge of the cell where the cage carries on
+    let start = |edge: bool| if edge { near } else { 0.0 };
+    let end = |edge: bool| if edge { far } else { 1.0 };
+
+    let mut lines: Vec<((f64, f64), (f64, f64))> = Vec::new();
+    if top {
+        lines.push(((start(left), near), (end(right), near)));
+    }
+    if right {
+        lines.push(((far, start(top)), (far, end(bottom))));
+    }
+    if bottom {
+        lines.push(((start(left), far), (end(right), far)));
+    }
+    if left {
+        lines.push(((near, start(top)), (near, end(bottom))));
+    }
+
+    // where the cage goes around the corner of a cell that isn't in it, the
+    // lines from the two cells next to that corner meet inside this one
+    for (down, across) in [(-1, -1), (-1, 1), (1, -1), (1, 1)].iter() {
+        if joined(*down, 0) && joined(0, *across) && !joined(*down, *across) {
+            let (x, edge_x) = if *across < 0 { (near, 0.0) } else { (far, 1.0) };
+            let (y, edge_y) = if *down < 0 { (near, 0.0) } else { (far, 1.0) };
+            lines.push(((x, edge_y), (x, y)));
+            lines.push(((x, y), (edge_x, y)));
+        }
+    }
+    lines
+}
+
+// the cell a cage's sum is written in, its top left one
+pub(crate) fn cage_label_cell(cage: &Cage) -> Option<i32> {
+    cage.cells.iter().min().copied()
+}
+
+// which notes of each cell can be part of the cells adding up to `sum`. a
+// filled in cell only has its value, and cells that are peers can't have the
+// same number. None if a cell doesn't have any notes left
+pub(crate) fn fitting_notes(board: &BigBox, cells: &[i32], sum: i32) -> Option<Vec<Vec<i32>>> {
+    let options: Vec<Vec<i32>> = cells
+        .iter()
+        .map(
+            |cell| match board.little_boxes[*cell as usize].get_value() {
+                Some(value) if board.layout().is_number(value) => vec![value],
+                _ => board.little_boxes[*cell as usize].get_possibles(),
+            },
+        )
+        .collect();
+    if options.iter().any(|notes| notes.is_empty()) {
+        return None;
+    }
+
+    let peers: Vec<Vec<bool>> = cells
+        .iter()
+        .map(|a| {
+            let affected = board.get_all_affected_indices(*a);
+            cells
+                .iter()
+                .map(|b| a != b && affected.contains(b))
+                .collect()
+        })
+        .collect();
+
+    let mut fitting: Vec<Vec<i32>> = vec![Vec::new(); cells.len()];
+    for (position, notes) in options.iter().enumerate() {
+        for note in notes {
+            let mut chosen = vec![0; cells.len()];
+            chosen[position] = *note;
+            if fill(&options, &peers, &mut chosen, 0, sum - note) {
+                fitting[position].push(*note);
+            }
+        }
+    }
+    Some(fitting)
+}
+
+// try to fill in the rest of `chosen` (the cells that are still 0) from their
+// options so they add up to `remaining`
+fn fill(
+    options: &[Vec<i32>],
+    peers: &[Vec<bool>],
+    chosen: &mut Vec<i32>,
+    position: usize,
+    remaining: i32,
+) -> bool {
+    if position == chosen.len() {
+        return remaining == 0;
+    }
+    if chosen[position] != 0 {
+        return fill(options, peers, chosen, position + 1, remaining);
+    }
+
+    // the smallest and biggest the other empty cells after this one could add
+    // up to, the options are smallest first
+    let (low, high) = (position + 1..chosen.len())
+        .filter(|other| chosen[*other] == 0)
+        .fold((0, 0), |(low, high), other| {
+            (
+                low + options[other][0],
+                high + options[other][options[other].len() - 1],
+            )
+        });
+
+    for note in &options[position] {
+        if note + low > remaining || note + high < remaining {
+            continue;
+        }
+        if (0..chosen.len()).any(|other| peers[position][other] && chosen[other] == *note) {
+            continue;
+        }
+
+        chosen[position] = *note;
+        let filled = fill(options, peers, chosen, position + 1, remaining - note);
+        chosen[position] = 0;
+        if filled {
+            return true;
+        }
+    }
+    false
+}
+
+// a step taking out every note that can't be part of the cells adding up to
+// `sum`, None if they all can
+fn sum_step(technique: &dyn Technique, board: &BigBox, cells: &[i32], sum: i32) -> Option<Step> {
+    let fitting = fitting_notes(board, cells, sum)?;
+
+    let mut step = Step::new(technique);
+    for (cell, notes) in cells.iter().zip(&fitting) {
+        for note in board.little_boxes[*cell as usize].get_possibles() {
+            if !notes.contains(&note) {
+                step.eliminations.push((*cell, note));
+            }
+        }
+    }
+
+    if step.is_empty() {
+        None
+    } else {
+        Some(step)
+    }
+}
+
+// groups of cells with a sum that's known from the cages: the innies and outies
+// of every unit, and of runs of rows or columns next to each other
+fn innies_and_outies(board: &BigBox) -> Vec<(Vec<i32>, i32)> {
+    let layout = board.layout();
+    let size = layout.size();
+    let unit_sum = size * (size + 1) / 2;
+
+    // every area and how many units it's made of
+    let mut areas: Vec<(Vec<i32>, i32)> = board
+        .get_all_groups_indexes()
+        .into_iter()
+        .map(|unit| (unit, 1))
+        .collect();
+    for lines in [layout.rows(), layout.cols()].iter() {
+        for length in 2..lines.len() {
+            for first in 0..=lines.len() - length {
+                areas.push((lines[first..first + length].concat(), length as i32));
+            }
+        }
+    }
+
+    let mut groups: Vec<(Vec<i32>, i32)> = Vec::new();
+    for (area, units) in areas {
+        let total = units * unit_sum;
+        let mut in_area = vec![false; layout.cell_count() as usize];
+        for cell in &area {
+            in_area[*cell as usize] = true;
+        }
+
+        // the cages that are all inside the area, and the ones that have any
+        // cells in it
+        let inside: Vec<_> = layout
+            .cages()
+            .iter()
+            .filter(|cage| cage.cells.iter().all(|cell| in_area[*cell as usize]))
+            .collect();
+        let touching: Vec<_> = layout
+            .cages()
+            .iter()
+            .filter(|cage| cage.cells.iter().any(|cell| in_area[*cell as usize]))
+            .collect();
+
+        // innies, the cells of the area that aren't in a cage inside it
+        let innies: Vec<i32> = area
+            .iter()
+            .filter(|cell| !inside.iter().any(|cage| cage.cells.contains(cell)))
+            .copied()
+            .collect();
+        if !innies.is_empty() && innies.len() < area.len() {
+            let sum = total - inside.iter().map(|cage| cage.sum).sum::<i32>();
+            groups.push((innies, sum));
+        }
+
+        // outies, the cells that stick out of the area from the cages touching
+        // it. only works when every cell of the area is in one of them
+        let caged: usize = touching
+            .iter()
+            .map(|cage| {
+                cage.cells
+                    .iter()
+                    .filter(|cell| in_area[**cell as usize])
+                    .count()
+            })
+            .sum();
+        if caged == area.len() {
+            let outies: Vec<i32> = touching
+                .iter()
+                .flat_map(|cage| cage.cells.iter())
+                .filter(|cell| !in_area[**cell as usize])
+                .copied()
+                .collect();
+            if !outies.is_empty() {
+                let sum = touching.iter().map(|cage| cage.sum).sum::<i32>() - total;
+                groups.push((outies, sum));
+            }
+        }
+    }
+
+    for (cells, _) in groups.iter_mut() {
+        cells.sort_unstable();
+    }
+    groups.sort();
+    groups.dedup();
+    groups
+}
+
+// true if every cell in the group is filled in, there's nothing to find
+fn all_filled(board: &BigBox, cells: &[i32]) -> bool {
+    cells
+        .iter()
+        .all(|cell| board.little_boxes[*cell as usize].get_value().is_some())
+}
+
+// notes that can't be part of any set of numbers adding up to a cage's sum
+pub struct CageCombinations;
+
+impl Technique for CageCombinations {
+    fn name(&self) -> &str {
+        "Cage Combinations"
+    }
+
+    fn difficulty(&self) -> f32 {
+        2.0
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        board
+            .layout()
+            .cages()
+            .iter()
+            .filter(|cage| !all_filled(board, &cage.cells))
+            .find_map(|cage| sum_step(self, board, &cage.cells, cage.sum))
+    }
+}
+
+// the 45 rule: the cells of a unit (or a few units together) that aren't in a
+// cage inside it add up to whatever the cages leave, and so do the cells of
+// the cages that stick out of it
+pub struct InniesAndOuties;
+
+impl Technique for InniesAndOuties {
+    fn name(&self) -> &str {
+        "Innies and Outies"
+    }
+
+    fn difficulty(&self) -> f32 {
+        2.5
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        if board.layout().cages().is_empty() {
+            return None;
+        }
+
+        innies_and_outies(board)
+            .iter()
+            .filter(|(cells, _)| cells.len() <= MAX_SUM_GROUP && !all_filled(board, cells))
+            .find_map(|(cells, sum)| sum_step(self, board, cells, *sum))
+    }
+}
+
+// when an innie or outie group is part of a cage, the rest of the cage adds
+// up to what's left of the cage's sum
+pub struct CageSplitting;
+
+impl Technique for CageSplitting {
+    fn name(&self) -> &str {
+        "Cage Splitting"
+    }
+
+    fn difficulty(&self) -> f32 {
+        2.9
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        if board.layout().cages().is_empty() {
+            return None;
+        }
+
+        for (group, sum) in innies_and_outies(board) {
+            for cage in board.layout().cages() {
+                if group.len() >= cage.cells.len()
+                    || !group.iter().all(|cell| cage.cells.contains(cell))
+                {
+                    continue;
+                }
+
+                let rest: Vec<i32> = cage
+                    .cells
+                    .iter()
+                    .filter(|cell| !group.contains(cell))
+                    .copied()
+                    .collect();
+                if rest.len() > MAX_SUM_GROUP || all_filled(board, &rest) {
+                    continue;
+                }
+                if let Some(step) = sum_step(self, board, &rest, cage.sum - sum) {
+                    return Some(step);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{board_with, eliminations};
+
+    // every note but `keep` in a cell
+    fn all_but(cell: i32, keep: &[i32]) -> Vec<(i32, i32)> {
+        (1..=9)
+            .filter(|note| !keep.contains(note))
+            .map(|note| (cell, note))
+            .collect()
+    }
+
+    #[test]
+    fn cages_round_trip() {
+        let layout = Layout::standard();
+        let cages = parse_cages(&layout, "3: r1c1 r1c2\n\n 17: R9C8, r9c9 ").unwrap();
+        assert_eq!(
+            cages,
+            vec![Cage::new(3, vec![0, 1]), Cage::new(17, vec![79, 80])]
+        );
+
+        let layout = layout.with_cages(cages).unwrap();
+        assert_eq!(write_cages(&layout), "3: r1c1 r1c2\n17: r9c8 r9c9\n");
+        assert_eq!(
+            parse_cages(&layout, &write_cages(&layout)).unwrap(),
+            layout.cages()
+        );
+
+        assert!(parse_cages(&layout, "r1c1 r1c2").is_err());
+        assert!(parse_cages(&layout, "3 r1c1").is_err());
+        assert!(parse_cages(&layout, "3: r1c10").is_err());
+    }
+
+    #[test]
+    fn cage_combinations() {
+        // only 1 and 2 add up to 3
+        let cages = vec![Cage::new(3, vec![0, 1])];
+        let board = board_with(Layout::standard().with_cages(cages).unwrap(), &[]);
+        let mut expected = all_but(0, &[1, 2]);
+        expected.extend(all_but(1, &[1, 2]));
+        assert_eq!(eliminations(&CageCombinations, &board), expected);
+    }
+
+    #[test]
+    fn the_45_rule() {
+        // the first 8 cells of the top row add up to 36, so the last one is 9
+        let cages = vec![Cage::new(36, (0..8).collect())];
+        let board = board_with(Layout::standard().with_cages(cages).unwrap(), &[]);
+        assert_eq!(eliminations(&InniesAndOuties, &board), all_but(8, &[9]));
+        assert!(InniesAndOuties
+            .find(&BigBox::new(vec![0; 81]).unwrap())
+            .is_none());
+    }
+
+    #[test]
+    fn cage_splitting() {
+        // the innie at the end of the top row is 9, so the rest of its cage is 3
+        let cages = vec![Cage::new(36, (0..8).collect()), Cage::new(12, vec![8, 17])];
+        let board = board_with(Layout::standard().with_cages(cages).unwrap(), &[]);
+        assert_eq!(eliminations(&CageSplitting, &board), all_but(17, &[3]));
+    }
+}
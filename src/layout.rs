@@ -0,0 +1,940 @@
+// the shape of a board. a board is `size` by `size` cells and needs the numbers
+// 1 to `size` once in every row, column and box, where the boxes are
+// `box_width` by `box_height` cells. the usual sudoku is 3 by 3 boxes, kids'
+// puzzles are 4x4 with 2 by 2 boxes, 6x6 puzzles have 3 by 2 boxes and so on up
+// to 25x25 with 5 by 5 boxes.
+//
+// numbers past 9 are written as letters, A is 10 up to P for 25.
+//
+// jigsaw puzzles swap the boxes for regions of any shape. each region still
+// has `size` cells that all touch each other, and the region map says which
+// region every cell is in.
+//
+// variants can add more units on top: on an x-sudoku both long diagonals need
+// every number once as well. windoku (hyper sudoku) has four more boxes, the
+// windows, a cell in from the edges with a line of cells between them. the
+// cells outside the windows make up five more "ghost" regions that end up
+// needing every number once as well, so they're units too.
+//
+// anti-knight and anti-king puzzles don't add units, they make more cells
+// peers: no two cells a chess knight's move (or king's move) apart can have the
+// same number.
+//
+// non-consecutive puzzles have a rule between cells instead: cells side by side
+// can't have numbers one apart. kropki dots and Xs and Vs on the lines between
+// cells are rules like that too, see relations.rs. so are thermometers and
+// german whispers, lines drawn through the cells, and arrows are drawn the same
+// way, see lines.rs.
+//
+// killer sudoku has cages, see killer.rs. the cells in a cage can't repeat a
+// number, so they're peers of each other, but a cage isn't a unit because it
+// doesn't have to have every number.
+
+use crate::{Cage, Line, LineKind, Marker};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SavedLayout"))]
+pub struct Layout {
+    box_width: i32,
+    box_height: i32,
+    // the region number of every cell for a jigsaw, None for the usual boxes
+    regions: Option<Vec<i32>>,
+    // x-sudoku, the two long diagonals are units too
+    diagonals: bool,
+    // windoku, the windows and ghost regions are units too
+    windows: bool,
+    // cells a knight's move apart can't have the same number
+    anti_knight: bool,
+    // cells a king's move apart can't have the same number
+    anti_king: bool,
+    // cells side by side can't have numbers one apart
+    non_consecutive: bool,
+    // kropki dots, Xs and Vs between cells side by side, the smaller index
+    // first. no two on the same line
+    markers: Vec<(i32, i32, Marker)>,
+    // the negative constraints, cells without a dot (or without an X or V)
+    // between them can't be like they would be with one
+    negative_kropki: bool,
+    negative_xv: bool,
+    // thermometers, arrows and whispers
+    lines: Vec<Line>,
+    // killer sudoku cages, no cell is in more than one
+    cages: Vec<Cage>,
+}
+
+// the smallest board, 4x4 with 2 by 2 boxes
+const MIN_SIZE: i32 = 4;
+// the biggest board there are symbols for, P is 25
+const MAX_SIZE: i32 = 25;
+
+impl Layout {
+    // boxes that are `box_width` cells across and `box_height` cells down. None
+    // if a box would be less than 2 cells either way (a box one cell tall is
+    // just a row) or the board would be outside 4x4 to 25x25
+    pub fn new(box_width: i32, box_height: i32) -> Option<Layout> {
+        if box_width < 2
+            || box_height < 2
+            || !(MIN_SIZE..=MAX_SIZE).contains(&(box_width * box_height))
+        {
+            return None;
+        }
+        Some(Layout {
+            box_width,
+            box_height,
+            regions: None,
+            diagonals: false,
+            windows: false,
+            anti_knight: false,
+            anti_king: false,
+            non_consecutive: false,
+            markers: Vec::new(),
+            negative_kropki: false,
+            negative_xv: false,
+            lines: Vec::new(),
+            cages: Vec::new(),
+        })
+    }
+
+    // the usual 9x9 board
+    pub fn standard() -> Layout {
+        Layout {
+            box_width: 3,
+            box_height: 3,
+            regions: None,
+            diagonals: false,
+            windows: false,
+            anti_knight: false,
+            anti_king: false,
+            non_consecutive: false,
+            markers: Vec::new(),
+            negative_kropki: false,
+            negative_xv: false,
+            lines: Vec::new(),
+            cages: Vec::new(),
+        }
+    }
+
+    // a jigsaw board where `regions[index]` is the region of each cell,
+    // numbered from 0. the board is as big as the region map, and every region
+    // has to be `size` cells that are all joined up
+    pub fn jigsaw(regions: Vec<i32>) -> Result<Layout, String> {
+        let layout = Layout::for_cell_count(regions.len()).ok_or_else(|| {
+            format!(
+                "region map has {} cells, it should have 81 (or 16, 36, ...)",
+                regions.len()
+            )
+        })?;
+        let size = layout.size();
+
+        for region in 0..size {
+            let cells: Vec<i32> = (0..layout.cell_count())
+                .filter(|index| regions[*index as usize] == region)
+                .collect();
+            if cells.len() != size as usize {
+                return Err(format!(
+                    "region {} has {} cells, it should have {}",
+                    region + 1,
+                    cells.len(),
+                    size
+                ));
+            }
+
+            // spread out from the first cell, through neighbours in the same
+            // region, and see if that gets to all of them
+            let mut joined = vec![cells[0]];
+            let mut next = 0;
+            while next < joined.len() {
+                for neighbour in layout.neighbours(joined[next]) {
+                    if regions[neighbour as usize] == region && !joined.contains(&neighbour) {
+                        joined.push(neighbour);
+                    }
+                }
+                next += 1;
+            }
+            if joined.len() != cells.len() {
+                return Err(format!("region {} is in more than one piece", region + 1));
+            }
+        }
+
+        // the cells have all been counted into regions 0 to size - 1, so
+        // there's nothing else in the map
+        Ok(Layout {
+            regions: Some(regions),
+            ..layout
+        })
+    }
+
+    // a region map written out as text, one character per cell. each
+    // different character is a different region, so `AAABBBCCC...` or
+    // `111222333...` both work. spaces and line breaks are skipped
+    pub fn parse_regions(text: &str) -> Result<Layout, String> {
+        let mut names: Vec<char> = Vec::new();
+        let mut regions: Vec<i32> = Vec::new();
+        for c in text.chars().filter(|c| !c.is_whitespace()) {
+            let region = match names.iter().position(|name| *name == c) {
+                Some(region) => region,
+                None => {
+                    names.push(c);
+                    names.len() - 1
+                }
+            };
+            regions.push(region as i32);
+        }
+        Layout::jigsaw(regions)
+    }
+
+    // the same layout with every cell moved to `moved_to[index]`, for boards
+    // that have been turned or flipped. a jigsaw's regions, the cages, the
+    // markers and the lines go with their cells
+    pub(crate) fn moved(&self, moved_to: &[i32]) -> Layout {
+        let regions = self.regions.as_ref().map(|regions| {
+            let mut moved = regions.clone();
+            for (index, region) in regions.iter().enumerate() {
+                moved[moved_to[index] as usize] = *region;
+            }
+            moved
+        });
+        let cages = self
+            .cages
+            .iter()
+            .map(|cage| {
+                Cage::new(
+                    cage.sum,
+                    cage.cells
+                        .iter()
+                        .map(|cell| moved_to[*cell as usize])
+                        .collect(),
+                )
+            })
+            .collect();
+        let markers = self
+            .markers
+            .iter()
+            .map(|(a, b, marker)| {
+                let (a, b) = (moved_to[*a as usize], moved_to[*b as usize]);
+                (a.min(b), a.max(b), *marker)
+            })
+            .collect();
+
+        let lines = self
+            .lines
+            .iter()
+            .map(|line| {
+                Line::new(
+                    line.kind,
+                    line.cells
+                        .iter()
+                        .map(|cell| moved_to[*cell as usize])
+                        .collect(),
+                )
+            })
+            .collect();
+
+        Layout {
+            regions,
+            markers,
+            lines,
+            cages,
+            ..self.clone()
+        }
+    }
+
+    // the same layout as an x-sudoku, where both long diagonals have to have
+    // every number once
+    pub fn with_diagonals(self) -> Layout {
+        Layout {
+            diagonals: true,
+            ..self
+        }
+    }
+
+    pub fn has_diagonals(&self) -> bool {
+        self.diagonals
+    }
+
+    // the same layout as a windoku. the windows are the same size as the boxes
+    // and need square boxes to fit, so None for boards like 6x6
+    pub fn with_windows(self) -> Option<Layout> {
+        if self.box_width != self.box_height || self.box_width < 2 {
+            return None;
+        }
+        Some(Layout {
+            windows: true,
+            ..self
+        })
+    }
+
+    pub fn has_windows(&self) -> bool {
+        self.windows
+    }
+
+    // the same layout where cells a chess knight's move apart can't have the
+    // same number
+    pub fn with_anti_knight(self) -> Layout {
+        Layout {
+            anti_knight: true,
+            ..self
+        }
+    }
+
+    pub fn has_anti_knight(&self) -> bool {
+        self.anti_knight
+    }
+
+    // the same layout where cells a chess king's move apart can't have the
+    // same number. the king's straight moves are in the same row or column
+    // anyway, so it's the cells touching diagonally that matter
+    pub fn with_anti_king(self) -> Layout {
+        Layout {
+            anti_king: true,
+            ..self
+        }
+    }
+
+    pub fn has_anti_king(&self) -> bool {
+        self.anti_king
+    }
+
+    // the same layout where cells side by side (not diagonally) can't have
+    // numbers that are one apart
+    pub fn with_non_consecutive(self) -> Layout {
+        Layout {
+            non_consecutive: true,
+            ..self
+        }
+    }
+
+    pub fn has_non_consecutive(&self) -> bool {
+        self.non_consecutive
+    }
+
+    // the same layout with kropki dots, Xs and Vs between cells. every marker
+    // has to be between two cells side by side, with at most one on each line
+    pub fn with_markers(self, markers: Vec<(i32, i32, Marker)>) -> Result<Layout, String> {
+        let mut sorted: Vec<(i32, i32, Marker)> = Vec::new();
+        for (a, b, marker) in markers {
+            for cell in [a, b].iter() {
+                if !(0..self.cell_count()).contains(cell) {
+                    return Err(format!("cell {} isn't on the board", cell));
+                }
+            }
+            if !self.neighbours(a).contains(&b) {
+                return Err(format!("cells {} and {} aren't side by side", a, b));
+            }
+            let (a, b) = (a.min(b), a.max(b));
+            if sorted
+                .iter()
+                .any(|(other_a, other_b, _)| (*other_a, *other_b) == (a, b))
+            {
+                return Err(format!("cells {} and {} have more than one marker", a, b));
+            }
+            sorted.push((a, b, marker));
+        }
+
+        Ok(Layout {
+            markers: sorted,
+            ..self
+        })
+    }
+
+    pub fn markers(&self) -> &[(i32, i32, Marker)] {
+        &self.markers
+    }
+
+    // the marker on the line between two cells
+    pub fn marker_between(&self, a: i32, b: i32) -> Option<Marker> {
+        let (a, b) = (a.min(b), a.max(b));
+        self.markers
+            .iter()
+            .find(|(other_a, other_b, _)| (*other_a, *other_b) == (a, b))
+            .map(|(_, _, marker)| *marker)
+    }
+
+    // the same layout with thermometers, arrows and whispers. every line has to
+    // go through at least two cells, each one touching the one before it
+    // (diagonally is fine) and none of them twice. a thermometer can't be
+    // longer than a row, there wouldn't be enough numbers to go up
+    pub fn with_lines(self, lines: Vec<Line>) -> Result<Layout, String> {
+        for line in &lines {
+            let name = line.kind.name();
+            if line.cells.len() < 2 {
+                return Err(format!("a {} has to go through at least two cells", name));
+            }
+            if line.kind == LineKind::Thermometer && line.cells.len() as i32 > self.size() {
+                return Err(format!(
+                    "a thermo can't be longer than {} cells",
+                    self.size()
+                ));
+            }
+            for (position, cell) in line.cells.iter().enumerate() {
+                if !(0..self.cell_count()).contains(cell) {
+                    return Err(format!("cell {} isn't on the board", cell));
+                }
+                if line.cells[..position].contains(cell) {
+                    return Err(format!("a {} goes through cell {} twice", name, cell));
+                }
+                if position > 0 {
+                    let before = line.cells[position - 1];
+                    let down = (self.row(*cell) - self.row(before)).abs();
+                    let across = (self.col(*cell) - self.col(before)).abs();
+                    if down > 1 || across > 1 {
+                        return Err(format!("a {} jumps from cell {} to {}", name, before, cell));
+                    }
+                }
+            }
+        }
+
+        Ok(Layout { lines, ..self })
+    }
+
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    // the same layout where cells side by side without a kropki dot between
+    // them can't be one apart, or one twice the other
+    pub fn with_negative_kropki(self) -> Layout {
+        Layout {
+            negative_kropki: true,
+            ..self
+        }
+    }
+
+    pub fn has_negative_kropki(&self) -> bool {
+        self.negative_kropki
+    }
+
+    // the same layout where cells side by side without an X or V between them
+    // can't add up to 10 or 5
+    pub fn with_negative_xv(self) -> Layout {
+        Layout {
+            negative_xv: true,
+            ..self
+        }
+    }
+
+    pub fn has_negative_xv(&self) -> bool {
+        self.negative_xv
+    }
+
+    // the cells a knight's or king's move away from a cell, for the chess
+    // constraints the layout has. empty if it doesn't have any
+    pub fn chess_peers(&self, index: i32) -> Vec<i32> {
+        let knight = [
+            (-2, -1),
+            (-2, 1),
+            (-1, -2),
+            (-1, 2),
+            (1, -2),
+            (1, 2),
+            (2, -1),
+            (2, 1),
+        ];
+        let king = [
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ];
+        let mut moves: Vec<(i32, i32)> = Vec::new();
+        if self.anti_knight {
+            moves.extend_from_slice(&knight);
+        }
+        if self.anti_king {
+            moves.extend_from_slice(&king);
+        }
+
+        let (row, col, size) = (self.row(index), self.col(index), self.size());
+        moves
+            .iter()
+            .map(|(down, across)| (row + down, col + across))
+            .filter(|(row, col)| (0..size).contains(row) && (0..size).contains(col))
+            .map(|(row, col)| row * size + col)
+            .collect()
+    }
+
+    // the same layout as a killer sudoku. every cage has to be on the board,
+    // no bigger than a row, with a sum its cells can add up to, and no cell can
+    // be in two cages
+    pub fn with_cages(self, cages: Vec<Cage>) -> Result<Layout, String> {
+        let mut caged: Vec<i32> = Vec::new();
+        for cage in &cages {
+            let cells = cage.cells.len() as i32;
+            if cells == 0 || cells > self.size() {
+                return Err(format!(
+                    "the {} cage has {} cells, it can only have 1 to {}",
+                    cage.sum,
+                    cells,
+                    self.size()
+                ));
+            }
+            // the smallest and biggest numbers that fit
+            let smallest = cells * (cells + 1) / 2;
+            let biggest = cells * (2 * self.size() - cells + 1) / 2;
+            if cage.sum < smallest || cage.sum > biggest {
+                return Err(format!("{} cells can't add up to {}", cells, cage.sum));
+            }
+            for cell in &cage.cells {
+                if !(0..self.cell_count()).contains(cell) {
+                    return Err(format!("cell {} isn't on the board", cell));
+                }
+                if caged.contains(cell) {
+                    return Err(format!("cell {} is in more than one cage", cell));
+                }
+                caged.push(*cell);
+            }
+        }
+
+        Ok(Layout { cages, ..self })
+    }
+
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    // the cage a cell is in
+    pub fn cage_of(&self, index: i32) -> Option<&Cage> {
+        self.cages.iter().find(|cage| cage.cells.contains(&index))
+    }
+
+    pub fn is_jigsaw(&self) -> bool {
+        self.regions.is_some()
+    }
+
+    // the region map of a jigsaw
+    pub fn regions(&self) -> Option<&[i32]> {
+        self.regions.as_deref()
+    }
+
+    // the usual boxes for a board size, as close to square as they go and wider
+    // than they are tall (6x6 gets 3 by 2 boxes, 12x12 gets 4 by 3). None
+    // outside 4x4 to 25x25, and for sizes where the boxes would only be one
+    // cell tall, like 7x7
+    pub fn for_size(size: i32) -> Option<Layout> {
+        if !(MIN_SIZE..=MAX_SIZE).contains(&size) {
+            return None;
+        }
+        let box_height = (2..=size)
+            .filter(|height| size % height == 0 && height * height <= size)
+            .max()?;
+        Layout::new(size / box_height, box_height)
+    }
+
+    // the usual layout for a board with this many cells, if it's a square of
+    // a size for_size takes
+    pub fn for_cell_count(cells: usize) -> Option<Layout> {
+        let size = (MIN_SIZE..=MAX_SIZE).find(|size| (size * size) as usize == cells)?;
+        Layout::for_size(size)
+    }
+
+    pub fn box_width(&self) -> i32 {
+        self.box_width
+    }
+
+    pub fn box_height(&self) -> i32 {
+        self.box_height
+    }
+
+    // how many cells across (and down) the board is, and the biggest number
+    pub fn size(&self) -> i32 {
+        self.box_width * self.box_height
+    }
+
+    pub fn cell_count(&self) -> i32 {
+        self.size() * self.size()
+    }
+
+    // the numbers that go in the cells
+    pub fn numbers(&self) -> Vec<i32> {
+        (1..=self.size()).collect()
+    }
+
+    pub fn is_number(&self, value: i32) -> bool {
+        (1..=self.size()).contains(&value)
+    }
+
+    pub fn row(&self, index: i32) -> i32 {
+        index / self.size()
+    }
+
+    pub fn col(&self, index: i32) -> i32 {
+        index % self.size()
+    }
+
+    // boxes are numbered left to right, top to bottom. on a jigsaw it's the
+    // cell's region
+    pub fn box_number(&self, index: i32) -> i32 {
+        if let Some(regions) = &self.regions {
+            return regions[index as usize];
+        }
+        let boxes_across = self.size() / self.box_width;
+        (self.row(index) / self.box_height) * boxes_across + self.col(index) / self.box_width
+    }
+
+    // the cells in a box, in reading order
+    pub fn box_cells(&self, box_number: i32) -> Vec<i32> {
+        if let Some(regions) = &self.regions {
+            return (0..self.cell_count())
+                .filter(|index| regions[*index as usize] == box_number)
+                .collect();
+        }
+        let boxes_across = self.size() / self.box_width;
+        let top = box_number / boxes_across * self.box_height;
+        let left = box_number % boxes_across * self.box_width;
+        (top..top + self.box_height)
+            .flat_map(|row| (left..left + self.box_width).map(move |col| (row, col)))
+            .map(|(row, col)| row * self.size() + col)
+            .collect()
+    }
+
+    // the cells up, right, down and left of a cell that are on the board
+    pub fn neighbours(&self, index: i32) -> Vec<i32> {
+        let (row, col) = (self.row(index), self.col(index));
+        let mut neighbours: Vec<i32> = Vec::new();
+        if row > 0 {
+            neighbours.push(index - self.size());
+        }
+        if col < self.size() - 1 {
+            neighbours.push(index + 1);
+        }
+        if row < self.size() - 1 {
+            neighbours.push(index + self.size());
+        }
+        if col > 0 {
+            neighbours.push(index - 1);
+        }
+        neighbours
+    }
+
+    // which sides of a cell (top, right, bottom, left) are the edge of its box
+    // or of the board, where the thick lines go
+    pub fn box_edges(&self, index: i32) -> [bool; 4] {
+        let size = self.size();
+        let (row, col) = (self.row(index), self.col(index));
+        let different = |other: i32| self.box_number(other) != self.box_number(index);
+        [
+            row == 0 || different(index - size),
+            col == size - 1 || different(index + 1),
+            row == size - 1 || different(index + size),
+            col == 0 || different(index - 1),
+        ]
+    }
+
+    // the same boxes turned on their side, for a board that's been rotated or
+    // transposed. a jigsaw's regions don't turn with it, the board moves them
+    // along with its cells
+    pub fn transposed(&self) -> Layout {
+        Layout {
+            box_width: self.box_height,
+            box_height: self.box_width,
+            regions: self.regions.clone(),
+            diagonals: self.diagonals,
+            windows: self.windows,
+            anti_knight: self.anti_knight,
+            anti_king: self.anti_king,
+            non_consecutive: self.non_consecutive,
+            markers: self.markers.clone(),
+            negative_kropki: self.negative_kropki,
+            negative_xv: self.negative_xv,
+            lines: self.lines.clone(),
+            cages: self.cages.clone(),
+        }
+    }
+
+    pub fn rows(&self) -> Vec<Vec<i32>> {
+        let size = self.size();
+        (0..size)
+            .map(|row| (0..size).map(|col| row * size + col).collect())
+            .collect()
+    }
+
+    pub fn cols(&self) -> Vec<Vec<i32>> {
+        let size = self.size();
+        (0..size)
+            .map(|col| (0..size).map(|row| row * size + col).collect())
+            .collect()
+    }
+
+    pub fn boxes(&self) -> Vec<Vec<i32>> {
+        (0..self.size())
+            .map(|box_number| self.box_cells(box_number))
+            .collect()
+    }
+
+    // top left to bottom right, then top right to bottom left
+    pub fn diagonals(&self) -> Vec<Vec<i32>> {
+        let size = self.size();
+        vec![
+            (0..size).map(|i| i * size + i).collect(),
+            (0..size).map(|i| i * size + size - 1 - i).collect(),
+        ]
+    }
+
+    // the windows of a windoku, then the ghost regions. every row (and column)
+    // either goes through a window, or is one of the lines between them (the
+    // rows 0, 4 and 8 on a normal board). splitting the rows and columns up
+    // that way gives the windows, and everything else is the ghost regions
+    fn window_units(&self) -> Vec<Vec<i32>> {
+        let gap = self.box_width + 1;
+        let lines_between = self.box_width - 1;
+        // which window a row or column goes through, counting from 0, or
+        // `lines_between` for the lines between the windows
+        let window = |line: i32| {
+            if line % gap == 0 {
+                lines_between
+            } else {
+                line / gap
+            }
+        };
+
+        let mut windows: Vec<Vec<i32>> = Vec::new();
+        let mut ghosts: Vec<Vec<i32>> = Vec::new();
+        for down in 0..self.box_width {
+            for across in 0..self.box_width {
+                let cells: Vec<i32> = (0..self.cell_count())
+                    .filter(|index| {
+                        window(self.row(*index)) == down && window(self.col(*index)) == across
+                    })
+                    .collect();
+                if down == lines_between || across == lines_between {
+                    ghosts.push(cells);
+                } else {
+                    windows.push(cells);
+                }
+            }
+        }
+        windows.append(&mut ghosts);
+        windows
+    }
+
+    // the windows of a windoku, without the ghost regions. none if the layout
+    // isn't a windoku
+    pub fn windows(&self) -> Vec<Vec<i32>> {
+        if !self.windows {
+            return Vec::new();
+        }
+        let mut units = self.window_units();
+        units.truncate(((self.box_width - 1) * (self.box_width - 1)) as usize);
+        units
+    }
+
+    // the units a variant adds on top of the rows, columns and boxes
+    pub fn extra_units(&self) -> Vec<Vec<i32>> {
+        let mut units: Vec<Vec<i32>> = Vec::new();
+        if self.diagonals {
+            units.append(&mut self.diagonals());
+        }
+        if self.windows {
+            units.append(&mut self.window_units());
+        }
+        units
+    }
+
+    // how a number is written, 1 to 9 then A onwards
+    pub fn symbol(value: i32) -> Option<char> {
+        match value {
+            1..=9 => std::char::from_digit(value as u32, 10),
+            10..=MAX_SIZE => Some((b'A' + (value - 10) as u8) as char),
+            _ => None,
+        }
+    }
+
+    // the number a symbol stands for, letters can be either case
+    pub fn value_of(symbol: char) -> Option<i32> {
+        match symbol {
+            '1'..='9' => Some(symbol as i32 - '0' as i32),
+            'A'..='P' => Some(symbol as i32 - 'A' as i32 + 10),
+            'a'..='p' => Some(symbol as i32 - 'a' as i32 + 10),
+            _ => None,
+        }
+    }
+}
+
+// a layout as it comes in from json. it's built back up through the same
+// functions as any other layout so a region map, a marker or a cage that doesn't
+// fit the board is an error instead of a panic later on
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SavedLayout {
+    box_width: i32,
+    box_height: i32,
+    regions: Option<Vec<i32>>,
+    diagonals: bool,
+    windows: bool,
+    anti_knight: bool,
+    anti_king: bool,
+    non_consecutive: bool,
+    markers: Vec<(i32, i32, Marker)>,
+    negative_kropki: bool,
+    negative_xv: bool,
+    lines: Vec<Line>,
+    cages: Vec<Cage>,
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<SavedLayout> for Layout {
+    type Error = String;
+
+    fn try_from(saved: SavedLayout) -> Result<Layout, String> {
+        let mut layout = Layout::new(saved.box_width, saved.box_height).ok_or_else(|| {
+            format!(
+                "a board can't have {} by {} boxes",
+                saved.box_width, saved.box_height
+            )
+        })?;
+        if let Some(regions) = saved.regions {
+            if regions.len() != layout.cell_count() as usize {
+                return Err(format!(
+                    "region map has {} cells, the board has {}",
+                    regions.len(),
+                    layout.cell_count()
+                ));
+            }
+            layout.regions = Layout::jigsaw(regions)?.regions;
+        }
+        if saved.windows {
+            layout = layout.with_windows().ok_or("windows need square boxes")?;
+        }
+        let layout = Layout {
+            diagonals: saved.diagonals,
+            anti_knight: saved.anti_knight,
+            anti_king: saved.anti_king,
+            non_consecutive: saved.non_consecutive,
+            negative_kropki: saved.negative_kropki,
+            negative_xv: saved.negative_xv,
+            ..layout
+        };
+        layout
+            .with_markers(saved.markers)?
+            .with_lines(saved.lines)?
+            .with_cages(saved.cages)
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Layout {
+        Layout::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_get_the_usual_boxes() {
+        let boxes =
+            |size| Layout::for_size(size).map(|layout| (layout.box_width(), layout.box_height()));
+        assert_eq!(boxes(4), Some((2, 2)));
+        assert_eq!(boxes(6), Some((3, 2)));
+        assert_eq!(boxes(9), Some((3, 3)));
+        assert_eq!(boxes(12), Some((4, 3)));
+        assert_eq!(boxes(16), Some((4, 4)));
+        assert_eq!(boxes(25), Some((5, 5)));
+    }
+
+    #[test]
+    fn sizes_without_boxes_are_none() {
+        for size in [0, 1, 2, 3, 5, 7, 11, 13, 17, 19, 23, 26, 36].iter() {
+            assert_eq!(Layout::for_size(*size), None, "size {}", size);
+        }
+        assert_eq!(Layout::for_cell_count(81), Some(Layout::standard()));
+        assert_eq!(Layout::for_cell_count(4), None);
+        assert_eq!(Layout::for_cell_count(49), None);
+        assert_eq!(Layout::for_cell_count(80), None);
+        assert_eq!(Layout::for_cell_count(289), None);
+
+        assert_eq!(Layout::new(1, 1), None);
+        assert_eq!(Layout::new(3, 1), None);
+        assert_eq!(Layout::new(1, 4), None);
+        assert_eq!(Layout::new(6, 5), None);
+        assert!(Layout::new(2, 3).is_some());
+    }
+
+    #[test]
+    fn jigsaw_regions() {
+        let layout = Layout::parse_regions("AAAB ABBB CCDD CCDD").unwrap();
+        assert!(layout.is_jigsaw());
+        assert_eq!(layout.box_cells(1), vec![3, 5, 6, 7]);
+        assert_eq!(layout.box_number(5), 1);
+
+        let standard: Vec<i32> = (0..81)
+            .map(|index| Layout::standard().box_number(index))
+            .collect();
+        assert_eq!(
+            Layout::jigsaw(standard).unwrap().boxes(),
+            Layout::standard().boxes()
+        );
+
+        assert!(Layout::parse_regions("AAAA ABBB CCDD CCDD").is_err());
+        assert_eq!(
+            Layout::parse_regions("ABAB ABAB CCDD CCDD"),
+            Err("region 1 is in more than one piece".to_string())
+        );
+        assert!(Layout::jigsaw(vec![0; 10]).is_err());
+    }
+
+    #[test]
+    fn diagonals_are_extra_units() {
+        let layout = Layout::standard().with_diagonals();
+        assert_eq!(
+            layout.diagonals()[0],
+            (0..9).map(|i| i * 10).collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            layout.diagonals()[1],
+            (1..=9).map(|i| i * 8).collect::<Vec<i32>>()
+        );
+        assert_eq!(layout.extra_units().len(), 2);
+        assert!(Layout::standard().extra_units().is_empty());
+
+        let board = crate::BigBox::with_layout(layout, vec![0; 81]).unwrap();
+        assert!(board.get_all_affected_indices(0).contains(&80));
+        assert!(!board.get_all_affected_indices(1).contains(&80));
+    }
+
+    #[test]
+    fn windows_and_ghost_regions() {
+        let layout = Layout::standard().with_windows().unwrap();
+        let windows = layout.windows();
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[0], vec![10, 11, 12, 19, 20, 21, 28, 29, 30]);
+
+        // the windows and ghost regions cover the board once, 9 cells each
+        let units = layout.extra_units();
+        assert_eq!(units.len(), 9);
+        assert!(units.iter().all(|unit| unit.len() == 9));
+        let mut cells: Vec<i32> = units.concat();
+        cells.sort_unstable();
+        assert_eq!(cells, (0..81).collect::<Vec<i32>>());
+
+        assert!(Layout::for_size(6).unwrap().with_windows().is_none());
+        assert!(Layout::standard().windows().is_empty());
+    }
+
+    #[test]
+    fn chess_moves_from_a_corner() {
+        assert!(Layout::standard().chess_peers(0).is_empty());
+        assert_eq!(
+            Layout::standard().with_anti_knight().chess_peers(0),
+            vec![11, 19]
+        );
+        assert_eq!(
+            Layout::standard().with_anti_king().chess_peers(0),
+            vec![1, 9, 10]
+        );
+        assert_eq!(
+            Layout::standard().with_anti_knight().chess_peers(40).len(),
+            8
+        );
+
+        let board =
+            crate::BigBox::with_layout(Layout::standard().with_anti_knight(), vec![0; 81]).unwrap();
+        assert!(board.get_all_affected_indices(0).contains(&19));
+    }
+}
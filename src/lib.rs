@@ -4,21 +4,91 @@
 #![allow(clippy::wildcard_imports)]
 
 use seed::{prelude::*, *};
-use std::{collections::HashMap, ops::RangeInclusive};
+
+mod booklet;
+mod canonical;
+mod formats;
+mod game;
+mod generator;
+mod killer;
+mod layout;
+mod lines;
+mod opensudoku;
+mod random;
+mod rating;
+mod relations;
+mod svg;
+mod symmetry;
+mod technique;
+#[cfg(test)]
+mod testing;
+mod text;
+mod transform;
+
+pub use booklet::{Booklet, BookletPuzzle, MAX_PUZZLES_PER_PAGE};
+pub use canonical::{canonical_form, is_equivalent};
+pub use formats::{read_sdk, read_sdm, read_ss, write_sdk, write_sdm, write_ss, SdkPuzzle};
+pub use game::GameState;
+pub use generator::{
+    count_solutions, count_solutions_with, daily_puzzle, daily_seed, generate_puzzle,
+    generate_puzzle_from, generate_symmetric_puzzle, minimize_puzzle, random_full_board,
+    unique_solution, unique_solution_with, GeneratedPuzzle,
+};
+pub use killer::{
+    parse_cages, write_cages, Cage, CageCombinations, CageSplitting, InniesAndOuties,
+};
+pub use layout::Layout;
+pub use lines::{parse_lines, write_lines, Arrow, GermanWhispers, Line, LineKind, Thermometer};
+pub use opensudoku::{read_opensudoku, write_opensudoku, OpenSudokuFolder};
+pub use random::Random;
+pub use rating::{rate_puzzle, rate_puzzle_with, Difficulty, Rating};
+pub use relations::{parse_markers, write_markers, Kropki, Marker, NonConsecutive, Xv};
+pub use svg::SvgOptions;
+pub use symmetry::Symmetry;
+pub use technique::{
+    combinations, BasicFish, Claiming, HiddenSingle, HiddenSubset, NakedSingle, NakedSubset,
+    Pipeline, Pointing, Step, Technique, XyWing,
+};
+pub use text::GridStyle;
 
 // ------ ------
 //     Init
 // ------ ------
 
 // `init` describes what should happen when your app started.
-fn init(_: Url, _: &mut impl Orders<Msg>) -> Model {
-    Model{
-        sudoku_board: BigBox{
-            times_updated: 0,
-            little_boxes: (0..=80).map(|i| LittleBox::new(0, i).unwrap()).collect(),
-        },
+// a link like `?seed=20210418&difficulty=hard` opens that generated puzzle
+fn init(url: Url, _: &mut impl Orders<Msg>) -> Model {
+    let mut model = Model {
+        sudoku_board: BigBox::new(vec![0; 81]).unwrap(),
         selected_box: None,
+        rating: None,
+        difficulty: Difficulty::Easy,
+        random: Random::new(js_sys::Date::now() as u64),
+        seed: None,
+        pasted_text: String::new(),
+        paste_error: None,
+        pasted_diagonals: false,
+        pasted_windows: false,
+        pasted_anti_knight: false,
+        pasted_anti_king: false,
+        pasted_non_consecutive: false,
+        pasted_negative: false,
+    };
+
+    let search_value = |key: &str| {
+        url.search()
+            .get(key)
+            .and_then(|values| values.first().cloned())
+    };
+    if let Some(difficulty) = search_value("difficulty").and_then(|value| value.parse().ok()) {
+        model.difficulty = difficulty;
     }
+    if let Some(seed) = search_value("seed").and_then(|value| value.parse().ok()) {
+        let puzzle = generate_puzzle_from(model.difficulty, seed);
+        show_generated_puzzle(&mut model, puzzle);
+    }
+
+    model
 }
 
 // ------ ------
@@ -29,17 +99,44 @@ fn init(_: Url, _: &mut impl Orders<Msg>) -> Model {
 struct Model {
     sudoku_board: BigBox,
     selected_box: Option<i32>,
+    rating: Option<Rating>,
+    difficulty: Difficulty,
+    random: Random,
+    // seed of the generated puzzle on the board, if it was generated
+    seed: Option<u64>,
+    pasted_text: String,
+    paste_error: Option<String>,
+    // load the pasted puzzle as an x-sudoku, a windoku, with the chess
+    // constraints, non-consecutive or with the negative constraint for its
+    // markers
+    pasted_diagonals: bool,
+    pasted_windows: bool,
+    pasted_anti_knight: bool,
+    pasted_anti_king: bool,
+    pasted_non_consecutive: bool,
+    pasted_negative: bool,
 }
 
 // ------ ------
 //    Update
 // ------ ------
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 enum Msg {
     SelectBox(i32),
     FillBox(Option<i32>),
     SolvePuzzle,
+    SetDifficulty(Difficulty),
+    NewPuzzle,
+    DailyPuzzle,
+    PastedTextChanged(String),
+    TogglePastedDiagonals,
+    TogglePastedWindows,
+    TogglePastedAntiKnight,
+    TogglePastedAntiKing,
+    TogglePastedNonConsecutive,
+    TogglePastedNegative,
+    LoadPastedPuzzle,
 }
 
 // `update` describes how to handle each `Msg`.
@@ -47,20 +144,194 @@ fn update(msg: Msg, model: &mut Model, _: &mut impl Orders<Msg>) {
     match msg {
         Msg::SelectBox(i) => {
             model.selected_box = Some(i);
-        },
+        }
         Msg::FillBox(Some(i)) => {
-
-            model.sudoku_board.update_little_box_value(&model.selected_box.unwrap(), &i);
-        },
+            model
+                .sudoku_board
+                .update_little_box_value(&model.selected_box.unwrap(), &i);
+        }
         Msg::FillBox(None) => {
-            model.sudoku_board.update_little_box_value(&model.selected_box.unwrap(), &0);
-        },
+            model
+                .sudoku_board
+                .update_little_box_value(&model.selected_box.unwrap(), &0);
+        }
         Msg::SolvePuzzle => {
+            model.rating = Some(rate_puzzle(&model.sudoku_board));
             model.sudoku_board.solve_puzzle();
         }
+        Msg::SetDifficulty(difficulty) => {
+            model.difficulty = difficulty;
+        }
+        Msg::NewPuzzle => {
+            // keep seeds short enough to type into a url by hand
+            let seed = model.random.next_u64() % 1_000_000_000;
+            let puzzle = generate_puzzle_from(model.difficulty, seed);
+            show_generated_puzzle(model, puzzle);
+        }
+        Msg::DailyPuzzle => {
+            // use the UTC date so everybody gets the same puzzle at the same time
+            let now = js_sys::Date::new_0();
+            let (year, month, day) = (
+                now.get_utc_full_year() as i32,
+                now.get_utc_month() + 1,
+                now.get_utc_date(),
+            );
+            let puzzle = daily_puzzle(year, month, day, model.difficulty);
+            show_generated_puzzle(model, puzzle);
+        }
+        Msg::PastedTextChanged(text) => {
+            model.pasted_text = text;
+        }
+        Msg::TogglePastedDiagonals => {
+            model.pasted_diagonals = !model.pasted_diagonals;
+        }
+        Msg::TogglePastedWindows => {
+            model.pasted_windows = !model.pasted_windows;
+        }
+        Msg::TogglePastedAntiKnight => {
+            model.pasted_anti_knight = !model.pasted_anti_knight;
+        }
+        Msg::TogglePastedAntiKing => {
+            model.pasted_anti_king = !model.pasted_anti_king;
+        }
+        Msg::TogglePastedNonConsecutive => {
+            model.pasted_non_consecutive = !model.pasted_non_consecutive;
+        }
+        Msg::TogglePastedNegative => {
+            model.pasted_negative = !model.pasted_negative;
+        }
+        Msg::LoadPastedPuzzle => {
+            // a jigsaw is pasted as the puzzle, an empty line, then its region
+            // map. a killer's cages, kropki or XV markers and thermometers,
+            // arrows or whispers go after another empty line, they're the part
+            // with the colons in it. cages start with their sum, markers and
+            // lines with their kind
+            let pasted_text = model.pasted_text.replace("\r\n", "\n");
+            let mut parts = pasted_text.trim().split("\n\n");
+            let puzzle_text = parts.next().unwrap_or("");
+            let (rules_text, regions_text): (Vec<&str>, Vec<&str>) =
+                parts.partition(|part| part.contains(':'));
+            let label = |line: &str| line.split(':').next().unwrap_or("").trim().to_lowercase();
+            let (cages_text, rules_text): (Vec<&str>, Vec<&str>) = rules_text
+                .iter()
+                .flat_map(|part| part.lines())
+                .partition(|line| label(line).parse::<i32>().is_ok());
+            let (markers_text, lines_text): (Vec<&str>, Vec<&str>) =
+                rules_text.into_iter().partition(|line| {
+                    Marker::ALL
+                        .iter()
+                        .any(|marker| marker.name() == label(line))
+                });
+            let (diagonals, windows) = (model.pasted_diagonals, model.pasted_windows);
+            let (anti_knight, anti_king) = (model.pasted_anti_knight, model.pasted_anti_king);
+            let (non_consecutive, negative) = (model.pasted_non_consecutive, model.pasted_negative);
+
+            let pasted = read_pasted_board(puzzle_text).and_then(|board| {
+                let mut layout = match regions_text.first() {
+                    Some(regions) => Layout::parse_regions(regions)?,
+                    None => board.layout().clone(),
+                };
+                if !cages_text.is_empty() {
+                    let cages = parse_cages(&layout, &cages_text.join("\n"))?;
+                    layout = layout.with_cages(cages)?;
+                }
+                if !markers_text.is_empty() {
+                    let markers = parse_markers(&layout, &markers_text.join("\n"))?;
+                    layout = layout.with_markers(markers)?;
+                }
+                if !lines_text.is_empty() {
+                    let lines = parse_lines(&layout, &lines_text.join("\n"))?;
+                    layout = layout.with_lines(lines)?;
+                }
+                // the negative constraint goes with the kinds of markers
+                // the puzzle has
+                let has_kropki = layout
+                    .markers()
+                    .iter()
+                    .any(|(_, _, marker)| marker.is_kropki());
+                let has_xv = layout
+                    .markers()
+                    .iter()
+                    .any(|(_, _, marker)| !marker.is_kropki());
+                if negative && has_kropki {
+                    layout = layout.with_negative_kropki();
+                }
+                if negative && has_xv {
+                    layout = layout.with_negative_xv();
+                }
+                if diagonals {
+                    layout = layout.with_diagonals();
+                }
+                if windows {
+                    layout = layout.with_windows().ok_or("windoku needs square boxes")?;
+                }
+                if anti_knight {
+                    layout = layout.with_anti_knight();
+                }
+                if anti_king {
+                    layout = layout.with_anti_king();
+                }
+                if non_consecutive {
+                    layout = layout.with_non_consecutive();
+                }
+
+                // the notes of a pencilmark grid, and which numbers were placed,
+                // go with the cells
+                board
+                    .with_layout_keeping_notes(layout)
+                    .ok_or_else(|| "the region map isn't the same size as the puzzle".to_string())
+            });
+            match pasted {
+                Ok(board) => {
+                    model.sudoku_board = board;
+                    model.rating = None;
+                    model.selected_box = None;
+                    model.seed = None;
+                    model.paste_error = None;
+                }
+                Err(e) => model.paste_error = Some(e),
+            }
+        }
     }
 }
 
+// a grid with notes in it first, if some cell has more than one number: all
+// its digits run together could also be a line for some other size of board.
+// otherwise the line (or a plain grid), which is read the same way for any size
+fn read_pasted_board(text: &str) -> Result<BigBox, String> {
+    match BigBox::from_pencilmark_grid(text) {
+        Ok(board)
+            if board
+                .little_boxes
+                .iter()
+                .any(|little_box| little_box.get_possibles().len() > 1) =>
+        {
+            Ok(board)
+        }
+        _ => text.parse::<BigBox>(),
+    }
+}
+
+// put a generated puzzle on the board, and its seed in the url so the link can
+// be shared. that's the seed that worked, which isn't always the one asked for
+fn show_generated_puzzle(model: &mut Model, puzzle: GeneratedPuzzle) {
+    let seed = puzzle.seed;
+    model.sudoku_board = puzzle.board;
+    model.rating = Some(puzzle.rating);
+    model.selected_box = None;
+    model.seed = Some(seed);
+
+    Url::current()
+        .set_search(UrlSearch::new(vec![
+            ("seed", vec![seed.to_string()]),
+            (
+                "difficulty",
+                vec![puzzle.difficulty.to_string().to_lowercase()],
+            ),
+        ]))
+        .go_and_replace();
+}
+
 // ------ ------
 //     View
 // ------ ------
@@ -68,30 +339,146 @@ fn update(msg: Msg, model: &mut Model, _: &mut impl Orders<Msg>) {
 #[allow(clippy::trivially_copy_pass_by_ref)]
 fn view(model: &Model) -> Vec<Node<Msg>> {
     nodes![
-        // I build the table like this... This, and other code in this project
-        // make me feel dirty...
-        table![
-            row(0..=8, &model.sudoku_board),
-            row(9..=17, &model.sudoku_board),
-            row(18..=26, &model.sudoku_board),
-            row(27..=35, &model.sudoku_board),
-            row(36..=44, &model.sudoku_board),
-            row(45..=53, &model.sudoku_board),
-            row(54..=62, &model.sudoku_board),
-            row(63..=71, &model.sudoku_board),
-            row(72..=80, &model.sudoku_board),
+        // a row of the table for every row of the board, however big it is
+        table![model
+            .sudoku_board
+            .layout()
+            .rows()
+            .iter()
+            .map(|cells| row(cells, &model.sudoku_board))
+            .collect::<Vec<Node<Msg>>>()],
+        button!("Solve!", ev(Ev::Click, move |_| { Msg::SolvePuzzle })),
+        rating_badge(&model.rating),
+        new_puzzle_controls(model),
+        paste_controls(model),
+    ]
+}
+
+// a box to paste a puzzle copied from somewhere else into
+fn paste_controls(model: &Model) -> Node<Msg> {
+    div![
+        textarea![
+            attrs!(
+                At::Rows => 9,
+                At::Cols => 30,
+                At::Placeholder => "paste a puzzle, 81 characters, a grid or a pencilmark grid. for a jigsaw add an empty line and the region map, for a killer an empty line and the cages, for kropki, XV, thermos, arrows or whispers an empty line and the markers or lines",
+            ),
+            input_ev(Ev::Input, Msg::PastedTextChanged)
+        ],
+        label![
+            input!(
+                attrs!(
+                    At::Type => "checkbox",
+                    At::Checked => model.pasted_diagonals.as_at_value(),
+                ),
+                ev(Ev::Click, |_| Msg::TogglePastedDiagonals)
+            ),
+            " x-sudoku (diagonals) "
+        ],
+        label![
+            input!(
+                attrs!(
+                    At::Type => "checkbox",
+                    At::Checked => model.pasted_windows.as_at_value(),
+                ),
+                ev(Ev::Click, |_| Msg::TogglePastedWindows)
+            ),
+            " windoku "
+        ],
+        label![
+            input!(
+                attrs!(
+                    At::Type => "checkbox",
+                    At::Checked => model.pasted_anti_knight.as_at_value(),
+                ),
+                ev(Ev::Click, |_| Msg::TogglePastedAntiKnight)
+            ),
+            " anti-knight "
+        ],
+        label![
+            input!(
+                attrs!(
+                    At::Type => "checkbox",
+                    At::Checked => model.pasted_anti_king.as_at_value(),
+                ),
+                ev(Ev::Click, |_| Msg::TogglePastedAntiKing)
+            ),
+            " anti-king "
+        ],
+        label![
+            input!(
+                attrs!(
+                    At::Type => "checkbox",
+                    At::Checked => model.pasted_non_consecutive.as_at_value(),
+                ),
+                ev(Ev::Click, |_| Msg::TogglePastedNonConsecutive)
+            ),
+            " non-consecutive "
+        ],
+        label![
+            input!(
+                attrs!(
+                    At::Type => "checkbox",
+                    At::Checked => model.pasted_negative.as_at_value(),
+                ),
+                ev(Ev::Click, |_| Msg::TogglePastedNegative)
+            ),
+            " negative constraint "
         ],
         button!(
-            "Solve!",
-            ev(Ev::Click, move |_| {
-                Msg::SolvePuzzle
+            "Load puzzle",
+            ev(Ev::Click, move |_| { Msg::LoadPastedPuzzle })
+        ),
+        model
+            .paste_error
+            .as_ref()
+            .map(|e| span![C!["text-danger"], format!(" {}", e)])
+    ]
+}
+
+// pick a difficulty and make a new puzzle with it
+fn new_puzzle_controls(model: &Model) -> Node<Msg> {
+    let selected = model.difficulty;
+    div![
+        select![
+            Difficulty::ALL.iter().map(|difficulty| option![
+                attrs!(At::Value => difficulty),
+                IF!(*difficulty == selected => attrs!(At::Selected => AtValue::None)),
+                difficulty.to_string()
+            ]),
+            input_ev(Ev::Change, |value| {
+                Msg::SetDifficulty(value.parse().unwrap_or(Difficulty::Easy))
             })
-        )
+        ],
+        button!("New puzzle", ev(Ev::Click, move |_| { Msg::NewPuzzle })),
+        button!(
+            "Puzzle of the day",
+            ev(Ev::Click, move |_| { Msg::DailyPuzzle })
+        ),
+        model.seed.map(|seed| span![format!(" puzzle #{}", seed)]),
     ]
 }
 
-fn row(range: RangeInclusive<i32>, sudoku_puzzle: &BigBox) -> Node<Msg> {
-    let boxes = range.map(|i| small_box(i, sudoku_puzzle)).collect::<Vec<Node<Msg>>>();
+// shows how hard the puzzle was once it's been solved
+fn rating_badge(rating: &Option<Rating>) -> Node<Msg> {
+    match rating {
+        Some(rating) => span![
+            C!["label", "label-info"],
+            attrs!(At::Title => match &rating.hardest_technique {
+                Some(technique) => format!("hardest step: {}", technique),
+                None => "nothing to solve".to_string(),
+            }),
+            rating.to_string()
+        ],
+        None => empty![],
+    }
+}
+
+fn row(cells: &[i32], sudoku_puzzle: &BigBox) -> Node<Msg> {
+    let boxes = cells
+        .iter()
+        .map(|i| small_box(*i, sudoku_puzzle))
+        .collect::<Vec<Node<Msg>>>();
     tr!(boxes)
 }
 
@@ -100,64 +487,272 @@ fn small_box(number: i32, sudoku_puzzle: &BigBox) -> Node<Msg> {
     // gives the cell a name for css
     let cell_name = format!("cell_{}", number);
     let saved_box_value = sudoku_puzzle.get_little_box_value(&number);
+    // thick borders along the edges of the medium boxes, or the regions on a jigsaw
+    let [top, right, bottom, left] = sudoku_puzzle.layout().box_edges(number);
+    let thick = |edge: bool| IF!(edge => "2px solid #000000");
+    // an x-sudoku's diagonals and a windoku's windows are shaded
+    let layout = sudoku_puzzle.layout();
+    let on_diagonal = layout.has_diagonals()
+        && layout
+            .diagonals()
+            .iter()
+            .any(|diagonal| diagonal.contains(&number));
+    let in_window = layout
+        .windows()
+        .iter()
+        .any(|window| window.contains(&number));
+    let shade = IF!(on_diagonal || in_window => "#E4E4F4");
+    // past 9 the numbers are letters, which a number input won't take
+    let size = layout.size();
+    let input_type = if size > 9 { "text" } else { "number" };
     td!(
+        style![
+            St::Position => "relative",
+            St::BorderTop => thick(top),
+            St::BorderRight => thick(right),
+            St::BorderBottom => thick(bottom),
+            St::BorderLeft => thick(left),
+        ],
         // this is very ugly, but I haven't found an easy way to sneak the At::Placeholder in if saved_box_value isn't none
         match saved_box_value {
-            Some(i) => {
+            Some(_) => {
                 input!(
                     attrs!(
                         At::Id => cell_name,
                         At::Name => cell_name,
                         At::MaxLength => 1,
-                        At::Type => "number",
-                        At::Placeholder => i
+                        At::Type => input_type,
+                        At::Placeholder => sudoku_puzzle.little_boxes[number as usize].get_pretty_value()
                     ),
+                    style![St::BackgroundColor => shade],
                     // selects the box, then parses the string, then if the number is okay, fill the box with it.
-                    input_ev(Ev::Input, move |_| {
-                        Msg::SelectBox(number)
-                    }),
-                    input_ev(Ev::Input, move |input| {
-                        let number = input.parse::<i32>();
-                        match number {
-                            Ok(i) if i >= 1 && i <= 9 => {
-                                Msg::FillBox(Some(i))
-                            },
-                            _ => {
-                                Msg::FillBox(None)
-                            }
-                        }
-                    }),
+                    input_ev(Ev::Input, move |_| { Msg::SelectBox(number) }),
+                    input_ev(Ev::Input, move |input| Msg::FillBox(typed_number(
+                        &input, size
+                    ))),
                 )
-            },
+            }
             None => {
                 input!(
                     attrs!(
                         At::Id => cell_name,
                         At::Name => cell_name,
                         At::MaxLength => 1,
-                        At::Type => "number",
+                        At::Type => input_type,
                     ),
+                    style![St::BackgroundColor => shade],
                     // selects the box, then parses the string, then if the number is okay, fill the box with it.
-                    input_ev(Ev::Input, move |_| {
-                        Msg::SelectBox(number)
-                    }),
-                    input_ev(Ev::Input, move |input| {
-                        let number = input.parse::<i32>();
-                        match number {
-                            Ok(i) if i >= 1 && i <= 9 => {
-                                Msg::FillBox(Some(i))
-                            },
-                            _ => {
-                                Msg::FillBox(None)
-                            }
-                        }
-                    }),
+                    input_ev(Ev::Input, move |_| { Msg::SelectBox(number) }),
+                    input_ev(Ev::Input, move |input| Msg::FillBox(typed_number(
+                        &input, size
+                    ))),
                 )
             }
-        }
+        },
+        line_overlay(number, layout),
+        cage_overlay(number, layout),
+        marker_overlay(number, layout),
     )
 }
 
+// what was typed into a cell, if it's one of the board's numbers. A is 10, B
+// is 11 and so on
+fn typed_number(input: &str, size: i32) -> Option<i32> {
+    let mut chars = input.trim().chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Layout::value_of(c).filter(|number| (1..=size).contains(number)),
+        _ => None,
+    }
+}
+
+// thermometers, arrows and whispers are drawn a cell at a time, from the
+// middle of the cell halfway to the cells before and after it on the line. the
+// bulb, the arrow's circle and its head go in their own cells
+fn line_overlay(number: i32, layout: &Layout) -> Vec<Node<Msg>> {
+    let mut shapes: Vec<Node<Msg>> = Vec::new();
+    let (x, y) = lines::cell_center(layout, number);
+    // from the board to this cell's svg, which is 100 across
+    let at = |(px, py): lines::Point| ((px - x) * 100.0 + 50.0, (py - y) * 100.0 + 50.0);
+    for line in layout.lines() {
+        let position = match line.cells.iter().position(|cell| *cell == number) {
+            Some(position) => position,
+            None => continue,
+        };
+        let (color, width) = match line.kind {
+            LineKind::Thermometer => ("#C0BFBC", 25.0),
+            LineKind::Arrow => ("#9A9996", 4.0),
+            LineKind::Whisper => ("#57E389", 12.0),
+        };
+        let arrow = match line.kind {
+            LineKind::Arrow => Some(lines::arrow_shape(layout, line, 0.4)),
+            _ => None,
+        };
+        let (from_x, from_y) = match &arrow {
+            Some((shaft, _)) => at(shaft[position]),
+            None => (50.0, 50.0),
+        };
+        let mut ends: Vec<(f64, f64)> = Vec::new();
+        for next in [position.wrapping_sub(1), position + 1].iter() {
+            if let Some(cell) = line.cells.get(*next) {
+                let (to_x, to_y) = at(lines::cell_center(layout, *cell));
+                ends.push(((50.0 + to_x) / 2.0, (50.0 + to_y) / 2.0));
+            }
+        }
+        if let Some((_, head)) = &arrow {
+            if position + 1 == line.cells.len() {
+                ends.extend(head.iter().map(|end| at(*end)));
+            }
+        }
+
+        if position == 0 && line.kind == LineKind::Thermometer {
+            shapes.push(circle!(attrs!(
+                At::Cx => 50,
+                At::Cy => 50,
+                At::R => 35,
+                At::Fill => color,
+            )));
+        }
+        if position == 0 && line.kind == LineKind::Arrow {
+            shapes.push(circle!(attrs!(
+                At::Cx => 50,
+                At::Cy => 50,
+                At::R => 40,
+                At::Fill => "none",
+                At::Stroke => color,
+                At::StrokeWidth => width,
+            )));
+        }
+        for (to_x, to_y) in ends {
+            shapes.push(line_!(attrs!(
+                At::X1 => from_x,
+                At::Y1 => from_y,
+                At::X2 => to_x,
+                At::Y2 => to_y,
+                At::Stroke => color,
+                At::StrokeWidth => width,
+                At::StrokeLinecap => "round",
+            )));
+        }
+    }
+    if shapes.is_empty() {
+        return Vec::new();
+    }
+    vec![svg!(
+        attrs!(
+            At::ViewBox => "0 0 100 100",
+            At::PreserveAspectRatio => "none",
+        ),
+        style![
+            St::Position => "absolute",
+            St::Top => "0",
+            St::Left => "0",
+            St::Width => "100%",
+            St::Height => "100%",
+            St::Overflow => "visible",
+            St::Opacity => "0.6",
+            St::PointerEvents => "none",
+        ],
+        shapes
+    )]
+}
+
+// kropki dots, Xs and Vs sit on the middle of the line between two cells. the
+// cell on the left (or on top) draws them, half sticking out into the other one
+fn marker_overlay(number: i32, layout: &Layout) -> Vec<Node<Msg>> {
+    layout
+        .markers()
+        .iter()
+        .filter(|(a, _, _)| *a == number)
+        .map(|(a, b, marker)| {
+            let right = *b == a + 1;
+            let (color, text) = match marker {
+                Marker::WhiteDot => (Some("#FFFFFF"), ""),
+                Marker::BlackDot => (Some("#000000"), ""),
+                Marker::X => (None, "X"),
+                Marker::V => (None, "V"),
+            };
+            span!(
+                style![
+                    St::Position => "absolute",
+                    St::Top => if right { "50%" } else { "100%" },
+                    St::Left => if right { "100%" } else { "50%" },
+                    St::Transform => "translate(-50%, -50%)",
+                    St::ZIndex => "1",
+                    St::PointerEvents => "none",
+                    St::Width => IF!(color.is_some() => "10px"),
+                    St::Height => IF!(color.is_some() => "10px"),
+                    St::BoxSizing => "border-box",
+                    St::BorderRadius => IF!(color.is_some() => "50%"),
+                    St::Border => IF!(color.is_some() => "1px solid #000000"),
+                    St::BackgroundColor => color.unwrap_or("#FFFFFF"),
+                    St::FontSize => "12px",
+                    St::FontWeight => "bold",
+                    St::LineHeight => "1",
+                ],
+                text
+            )
+        })
+        .collect()
+}
+
+// a killer's cage is drawn over the cell as dashed lines just inside its
+// edges, with the sum in the top left corner of the cage's first cell
+fn cage_overlay(number: i32, layout: &Layout) -> Vec<Node<Msg>> {
+    let outline = killer::cage_outline(layout, number, 0.08);
+    if outline.is_empty() {
+        return Vec::new();
+    }
+    let lines = outline
+        .iter()
+        .map(|((x1, y1), (x2, y2))| {
+            line_!(attrs!(
+                At::X1 => x1 * 100.0,
+                At::Y1 => y1 * 100.0,
+                At::X2 => x2 * 100.0,
+                At::Y2 => y2 * 100.0,
+                At::Stroke => "#3D3846",
+                At::StrokeWidth => 1,
+                At::StrokeDashArray => "4 3",
+                At::VectorEffect => "non-scaling-stroke",
+            ))
+        })
+        .collect::<Vec<Node<Msg>>>();
+    let sum = layout
+        .cage_of(number)
+        .filter(|cage| killer::cage_label_cell(cage) == Some(number))
+        .map(|cage| cage.sum);
+    vec![
+        svg!(
+            attrs!(
+                At::ViewBox => "0 0 100 100",
+                At::PreserveAspectRatio => "none",
+            ),
+            style![
+                St::Position => "absolute",
+                St::Top => "0",
+                St::Left => "0",
+                St::Width => "100%",
+                St::Height => "100%",
+                St::PointerEvents => "none",
+            ],
+            lines
+        ),
+        match sum {
+            Some(sum) => span!(
+                style![
+                    St::Position => "absolute",
+                    St::Top => "2px",
+                    St::Left => "4px",
+                    St::FontSize => "9px",
+                    St::PointerEvents => "none",
+                ],
+                sum.to_string()
+            ),
+            None => empty![],
+        },
+    ]
+}
+
 // ------ ------
 //     Start
 // ------ ------
@@ -169,111 +764,88 @@ pub fn start() {
     App::start("app", init, update, view);
 }
 
-
-const MEDIUM_BOX_1: [i32; 9] = [0, 1, 2, 9, 10, 11, 18, 19, 20];
-const MEDIUM_BOX_2: [i32; 9] = [3, 4, 5, 12, 13, 14, 21, 22, 23];
-const MEDIUM_BOX_3: [i32; 9] = [6, 7, 8, 15, 16, 17, 24, 25, 26];
-
-const MEDIUM_BOX_4: [i32; 9] = [27, 28, 29, 36, 37, 38, 45, 46, 47];
-const MEDIUM_BOX_5: [i32; 9] = [30, 31, 32, 39, 40, 41, 48, 49, 50];
-const MEDIUM_BOX_6: [i32; 9] = [33, 34, 35, 42, 43, 44, 51, 52, 53];
-
-const MEDIUM_BOX_7: [i32; 9] = [54, 55, 56, 63, 64, 65, 72, 73, 74];
-const MEDIUM_BOX_8: [i32; 9] = [57, 58, 59, 66, 67, 68, 75, 76, 77];
-const MEDIUM_BOX_9: [i32; 9] = [60, 61, 62, 69, 70, 71, 78, 79, 80];
-
-const ROW_STARTERS: [i32; 9] = [0, 9, 18, 27, 36, 45, 54, 63, 72];
-const COL_STARTERS: [i32; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
-const BOX_STARTERS: [i32; 9] = [0, 3, 6, 27, 30, 33, 54, 57, 60];
-
-const ALL_NOTES_POSSIBILITIES: [i32; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 9];
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "game::SavedLittleBox"))]
 pub struct LittleBox {
     value: Option<i32>,
     index: i32,
-    one_p: bool,
-    two_p: bool,
-    three_p: bool,
-    four_p: bool,
-    five_p: bool,
-    six_p: bool,
-    seven_p: bool,
-    eight_p: bool,
-    nine_p: bool,
+    // true if the value was part of the puzzle, not filled in afterwards
+    given: bool,
+    // the biggest number that goes in the cell, 9 on a normal board
+    size: i32,
+    // bit n is set while n is still possible
+    possibles: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "game::SavedBigBox"))]
 pub struct BigBox {
     pub little_boxes: Vec<LittleBox>,
     pub times_updated: u32,
+    layout: Layout,
 }
 
 impl LittleBox {
     pub fn new(value: i32, index: i32) -> Option<LittleBox> {
-        if value > 9 || value < 1 {
+        LittleBox::with_size(value, index, 9)
+    }
+
+    // a cell on a board that goes up to `size` instead of 9
+    pub fn with_size(value: i32, index: i32, size: i32) -> Option<LittleBox> {
+        if value > size || value < 1 {
             Some(LittleBox {
                 value: None,
                 index,
-                one_p: true,
-                two_p: true,
-                three_p: true,
-                four_p: true,
-                five_p: true,
-                six_p: true,
-                seven_p: true,
-                eight_p: true,
-                nine_p: true,
+                given: false,
+                size,
+                possibles: LittleBox::all_possibles(size),
             })
         } else {
             Some(LittleBox {
                 value: Some(value),
                 index,
-                one_p: false,
-                two_p: false,
-                three_p: false,
-                four_p: false,
-                five_p: false,
-                six_p: false,
-                seven_p: false,
-                eight_p: false,
-                nine_p: false,
+                given: true,
+                size,
+                possibles: 0,
             })
         }
     }
 
+    // bits 1 to size set
+    fn all_possibles(size: i32) -> u32 {
+        ((1u64 << (size + 1)) - 2) as u32
+    }
+
     pub fn get_value(&self) -> Option<i32> {
         self.value
     }
 
+    // was this value part of the puzzle (rather than placed by the player or solver)
+    pub fn is_given(&self) -> bool {
+        self.given && matches!(self.value, Some(i) if (1..=self.size).contains(&i))
+    }
+
     pub fn get_pretty_value(&self) -> String {
         let value = self.get_value();
         match value {
-            Some(i) if i >= 1 && i <= 9 => i.to_string(),
+            Some(i) if (1..=self.size).contains(&i) => Layout::symbol(i).unwrap_or(' ').to_string(),
             _ => " ".to_string(),
         }
     }
 
+    // a value placed after the puzzle was made, so it isn't a given any more
+    // even if the cell was one before
     pub fn update_value(&mut self, value: i32) {
         self.value = Some(value);
+        self.given = false;
         self.make_all_impossible();
     }
 
     pub fn make_impossible(&mut self, number: i32) -> bool {
-        let possibles = self.get_possibles();
-        if possibles.contains(&number) {
-            match number {
-                1 => self.one_p = false,
-                2 => self.two_p = false,
-                3 => self.three_p = false,
-                4 => self.four_p = false,
-                5 => self.five_p = false,
-                6 => self.six_p = false,
-                7 => self.seven_p = false,
-                8 => self.eight_p = false,
-                9 => self.nine_p = false,
-                _ => self.value = None, // this could never happen
-            }
+        if (1..=self.size).contains(&number) && self.possibles & (1 << number) != 0 {
+            self.possibles &= !(1 << number);
             true
         } else {
             false
@@ -292,62 +864,29 @@ impl LittleBox {
     }
 
     pub fn make_all_impossible(&mut self) {
-        for i in 1..=9 {
-            self.make_impossible(i);
-        }
+        self.possibles = 0;
     }
 
     // this is if something bad happens and the board needs to be rerun
     pub fn make_all_possible(&mut self) {
-        self.one_p = true;
-        self.two_p = true;
-        self.three_p = true;
-        self.four_p = true;
-        self.five_p = true;
-        self.six_p = true;
-        self.seven_p = true;
-        self.eight_p = true;
-        self.nine_p = true;
+        self.possibles = LittleBox::all_possibles(self.size);
     }
 
     pub fn get_possibles(&self) -> Vec<i32> {
-        let mut possibles: Vec<i32> = Vec::new();
-
-        if self.one_p {
-            possibles.push(1);
-        }
-        if self.two_p {
-            possibles.push(2);
-        }
-        if self.three_p {
-            possibles.push(3);
-        }
-        if self.four_p {
-            possibles.push(4);
-        }
-        if self.five_p {
-            possibles.push(5);
-        }
-        if self.six_p {
-            possibles.push(6);
-        }
-        if self.seven_p {
-            possibles.push(7);
-        }
-        if self.eight_p {
-            possibles.push(8);
-        }
-        if self.nine_p {
-            possibles.push(9);
-        }
-
-        possibles
+        (1..=self.size)
+            .filter(|number| self.possibles & (1 << number) != 0)
+            .collect()
     }
 }
 
 impl BigBox {
     pub fn new(values: Vec<i32>) -> Option<BigBox> {
-        if values.len() != 81 {
+        BigBox::with_layout(Layout::standard(), values)
+    }
+
+    // a board that isn't the usual 9x9, `values` has one number for every cell
+    pub fn with_layout(layout: Layout, values: Vec<i32>) -> Option<BigBox> {
+        if values.len() != layout.cell_count() as usize {
             log!(
                 "Puzzle length is incorrect. It's {} boxes long.",
                 values.len()
@@ -358,7 +897,7 @@ impl BigBox {
         let mut little_box_values: Vec<LittleBox> = Vec::new();
 
         for (index, value) in values.iter().enumerate() {
-            let new_little_box = LittleBox::new(*value, index as i32);
+            let new_little_box = LittleBox::with_size(*value, index as i32, layout.size());
             match new_little_box {
                 Some(lb) => {
                     little_box_values.push(lb);
@@ -372,6 +911,25 @@ impl BigBox {
         Some(BigBox {
             little_boxes: little_box_values,
             times_updated: 0,
+            layout,
+        })
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    // the same cells on another layout with as many cells, like when the rules
+    // of a pasted puzzle change. the values, the notes and which cells are clues
+    // all stay as they are. None if the layout is a different size
+    pub fn with_layout_keeping_notes(&self, layout: Layout) -> Option<BigBox> {
+        if layout.cell_count() as usize != self.little_boxes.len() {
+            return None;
+        }
+        Some(BigBox {
+            little_boxes: self.little_boxes.clone(),
+            times_updated: self.times_updated,
+            layout,
         })
     }
 
@@ -379,10 +937,10 @@ impl BigBox {
     // of possibilities
     pub fn update_little_box_value(&mut self, index: &i32, value: &i32) {
         let mut illegal = false;
-        for index in BigBox::get_all_affected_indices(*index) {
+        for index in self.get_all_affected_indices(*index) {
             match &self.little_boxes[index as usize].value {
                 Some(i) if i == value => illegal = true,
-                _ => ()
+                _ => (),
             }
         }
 
@@ -400,6 +958,28 @@ impl BigBox {
         self.little_boxes[*index as usize].value
     }
 
+    // get the values of every little box in order, 0 for the empty ones
+    pub fn get_values(&self) -> Vec<i32> {
+        self.little_boxes
+            .iter()
+            .map(|little_box| little_box.get_value().unwrap_or(0))
+            .collect()
+    }
+
+    // the same as get_values, but only the clues the puzzle started with
+    pub fn get_givens(&self) -> Vec<i32> {
+        self.little_boxes
+            .iter()
+            .map(|little_box| {
+                if little_box.is_given() {
+                    little_box.get_value().unwrap_or(0)
+                } else {
+                    0
+                }
+            })
+            .collect()
+    }
+
     // get times_updated
     pub fn get_times_updated(&self) -> u32 {
         self.times_updated
@@ -410,75 +990,61 @@ impl BigBox {
         self.times_updated += 1;
     }
 
-    // this gets box indexes given an index
-    pub fn get_box_indices(index: &i32) -> Vec<i32> {
-        let mut affected_indices: Vec<i32> = Vec::new();
-        if MEDIUM_BOX_1.contains(&index) {
-            for i in &MEDIUM_BOX_1 {
-                affected_indices.push(*i);
-            }
-        } else if MEDIUM_BOX_2.contains(&index) {
-            for i in &MEDIUM_BOX_2 {
-                affected_indices.push(*i);
-            }
-        } else if MEDIUM_BOX_3.contains(&index) {
-            for i in &MEDIUM_BOX_3 {
-                affected_indices.push(*i);
-            }
-        } else if MEDIUM_BOX_4.contains(&index) {
-            for i in &MEDIUM_BOX_4 {
-                affected_indices.push(*i);
-            }
-        } else if MEDIUM_BOX_5.contains(&index) {
-            for i in &MEDIUM_BOX_5 {
-                affected_indices.push(*i);
-            }
-        } else if MEDIUM_BOX_6.contains(&index) {
-            for i in &MEDIUM_BOX_6 {
-                affected_indices.push(*i);
-            }
-        } else if MEDIUM_BOX_7.contains(&index) {
-            for i in &MEDIUM_BOX_7 {
-                affected_indices.push(*i);
-            }
-        } else if MEDIUM_BOX_8.contains(&index) {
-            for i in &MEDIUM_BOX_8 {
-                affected_indices.push(*i);
-            }
-        } else if MEDIUM_BOX_9.contains(&index) {
-            for i in &MEDIUM_BOX_9 {
-                affected_indices.push(*i);
-            }
-        }
-
-        affected_indices
+    // this gets box indexes given an index (the region's on a jigsaw)
+    pub fn get_box_indices(&self, index: &i32) -> Vec<i32> {
+        self.layout.box_cells(self.layout.box_number(*index))
     }
 
-    pub fn get_row_indices(index: &i32) -> Vec<i32> {
-        let row_number = index / 9;
-        let start = row_number * 9;
-        (start..=start + 8).collect::<Vec<i32>>()
+    pub fn get_row_indices(&self, index: &i32) -> Vec<i32> {
+        let size = self.layout.size();
+        let start = self.layout.row(*index) * size;
+        (start..start + size).collect::<Vec<i32>>()
     }
 
-    pub fn get_col_indices(index: &i32) -> Vec<i32> {
-        let y = index / 9;
-        let x = index - (9 * y);
+    pub fn get_col_indices(&self, index: &i32) -> Vec<i32> {
+        let size = self.layout.size();
+        let x = self.layout.col(*index);
 
-        (0..=8).map(|i| (i * 9) + x).collect::<Vec<i32>>()
+        (0..size).map(|i| (i * size) + x).collect::<Vec<i32>>()
     }
 
-    pub fn get_all_affected_indices(index: i32) -> Vec<i32> {
+    pub fn get_all_affected_indices(&self, index: i32) -> Vec<i32> {
         let mut affected_indices: Vec<i32> = Vec::new();
 
-        for i in BigBox::get_box_indices(&index) {
+        for i in self.get_box_indices(&index) {
             affected_indices.push(i);
         }
-        for i in BigBox::get_col_indices(&index) {
+        // the diagonals on an x-sudoku and so on
+        for unit in self.layout.extra_units() {
+            if !unit.contains(&index) {
+                continue;
+            }
+            for i in unit {
+                if !affected_indices.contains(&i) {
+                    affected_indices.push(i);
+                }
+            }
+        }
+        // a killer's cages can't repeat numbers either
+        if let Some(cage) = self.layout.cage_of(index) {
+            for i in &cage.cells {
+                if !affected_indices.contains(i) {
+                    affected_indices.push(*i);
+                }
+            }
+        }
+        // and on anti-knight and anti-king boards, the cells a chess move away
+        for i in self.layout.chess_peers(index) {
+            if !affected_indices.contains(&i) {
+                affected_indices.push(i);
+            }
+        }
+        for i in self.get_col_indices(&index) {
             if !affected_indices.contains(&i) {
                 affected_indices.push(i)
             }
         }
-        for i in BigBox::get_row_indices(&index) {
+        for i in self.get_row_indices(&index) {
             if !affected_indices.contains(&i) {
                 affected_indices.push(i);
             }
@@ -489,8 +1055,8 @@ impl BigBox {
     }
 
     pub fn make_all_cells_all_possibilities_possible(&mut self) {
-        for index in 0..=80 {
-            if self.little_boxes[index as usize].get_value() == None {
+        for index in 0..self.layout.cell_count() {
+            if self.little_boxes[index as usize].get_value().is_none() {
                 self.little_boxes[index as usize].make_all_possible();
             }
         }
@@ -501,14 +1067,11 @@ impl BigBox {
         // format of tuple is index, value
         let mut affected_list: Vec<(i32, i32)> = Vec::new();
         for (i, value) in self.little_boxes.iter().enumerate() {
-            match value.value {
-                Some(ii) => {
-                    for affected in BigBox::get_all_affected_indices(i as i32) {
-                        // self.little_boxes[affected as usize].make_impossible(value.value);
-                        affected_list.push((affected, ii));
-                    }
-                },
-                _ => ()
+            if let Some(ii) = value.value {
+                for affected in self.get_all_affected_indices(i as i32) {
+                    // self.little_boxes[affected as usize].make_impossible(value.value);
+                    affected_list.push((affected, ii));
+                }
             }
         }
         affected_list
@@ -524,8 +1087,8 @@ impl BigBox {
     // scan each item in the puzzle to see what has only one possible solution
     pub fn scan_one_possible(&self) -> Vec<(i32, i32)> {
         let mut one_possible_list: Vec<(i32, i32)> = Vec::new();
-        for index in 0..=80 {
-            if self.little_boxes[index].value == None {
+        for index in 0..self.little_boxes.len() {
+            if self.little_boxes[index].value.is_none() {
                 let possibles = self.little_boxes[index].get_possibles();
 
                 // count number of possibilities and if 1, add it to the list
@@ -538,150 +1101,12 @@ impl BigBox {
         one_possible_list
     }
 
-    // scan for only one box can have a value in a row/col or med box
-    pub fn scan_for_one_possible_in_group(&mut self, index: i32) {
-        // check for box
-        let this_possible_list = self.little_boxes[index as usize].get_possibles();
-        let mut box_other_possible_list: Vec<i32> = Vec::new();
-
-        for i in BigBox::get_box_indices(&index) {
-            if i != index {
-                box_other_possible_list.append(&mut self.little_boxes[i as usize].get_possibles());
-            }
-        }
-        for this_possible in &this_possible_list {
-            if !box_other_possible_list.contains(&this_possible) {
-                self.add_valid_values(vec![(index, *this_possible)]);
-            }
-        }
-
-        // go again for columns
-        let mut box_other_possible_list: Vec<i32> = Vec::new();
-
-        for i in BigBox::get_col_indices(&index) {
-            if i != index {
-                box_other_possible_list.append(&mut self.little_boxes[i as usize].get_possibles());
-            }
-        }
-        for this_possible in &this_possible_list {
-            if !box_other_possible_list.contains(&this_possible) {
-                self.add_valid_values(vec![(index, *this_possible)]);
-            }
-        }
-
-        // go again for rows
-        let mut box_other_possible_list: Vec<i32> = Vec::new();
-        for i in BigBox::get_row_indices(&index) {
-            if i != index {
-                box_other_possible_list.append(&mut self.little_boxes[i as usize].get_possibles());
-            }
-        }
-        for this_possible in &this_possible_list {
-            if !box_other_possible_list.contains(&this_possible) {
-                self.add_valid_values(vec![(index, *this_possible)]);
-            }
-        }
-    }
-
-    // check if a medium box has a row or column with unique values to remove
-    // possible candidates from that same row or column in other medium boxes
-    pub fn find_medium_box_row_col_unique_possibles(&mut self, med_box: [i32; 9]) {
-        /*
-        The logic for this one doesn't have to be hard. Do it like this:
-        match the medium box by index
-        0 => get row1 and column1,
-        1 => get column2,
-        2 => get column3,
-        4 => get row2,
-        7 => get row3,
-        */
-
-        // make row and column index lists
-        let row1: Vec<i32> = vec![med_box[0], med_box[1], med_box[2]];
-        let row2: Vec<i32> = vec![med_box[3], med_box[4], med_box[5]];
-        let row3: Vec<i32> = vec![med_box[6], med_box[7], med_box[8]];
-
-        let col1: Vec<i32> = vec![med_box[0], med_box[3], med_box[6]];
-        let col2: Vec<i32> = vec![med_box[1], med_box[4], med_box[7]];
-        let col3: Vec<i32> = vec![med_box[2], med_box[5], med_box[8]];
-
-        // get row and column possibilities lists
-        let row1_p = self.get_group_possibles(&row1);
-        let row2_p = self.get_group_possibles(&row2);
-        let row3_p = self.get_group_possibles(&row3);
-
-        let col1_p = self.get_group_possibles(&col1);
-        let col2_p = self.get_group_possibles(&col2);
-        let col3_p = self.get_group_possibles(&col3);
-
-        // done finding everything, now mark the affected boxes
-        self.mark_list_impossible_outside_subgroup(
-            BigBox::get_row_indices(&row1[0]),
-            row1,
-            BigBox::main_vs_sub_unique_possibilities(&row1_p, &row2_p, &row3_p),
-        );
-        self.mark_list_impossible_outside_subgroup(
-            BigBox::get_row_indices(&row2[0]),
-            row2,
-            BigBox::main_vs_sub_unique_possibilities(&row2_p, &row1_p, &row3_p),
-        );
-        self.mark_list_impossible_outside_subgroup(
-            BigBox::get_row_indices(&row3[0]),
-            row3,
-            BigBox::main_vs_sub_unique_possibilities(&row3_p, &row2_p, &row1_p),
-        );
-
-        self.mark_list_impossible_outside_subgroup(
-            BigBox::get_col_indices(&col1[0]),
-            col1,
-            BigBox::main_vs_sub_unique_possibilities(&col1_p, &col2_p, &col3_p),
-        );
-        self.mark_list_impossible_outside_subgroup(
-            BigBox::get_col_indices(&col2[0]),
-            col2,
-            BigBox::main_vs_sub_unique_possibilities(&col2_p, &col1_p, &col3_p),
-        );
-        self.mark_list_impossible_outside_subgroup(
-            BigBox::get_col_indices(&col3[0]),
-            col3,
-            BigBox::main_vs_sub_unique_possibilities(&col3_p, &col2_p, &col1_p),
-        );
-    }
-
-    // mark impossible possibilities not in a subgroup
-    pub fn mark_list_impossible_outside_subgroup(
-        &mut self,
-        big_group: Vec<i32>,
-        small_group: Vec<i32>,
-        mark_list: Vec<i32>,
-    ) {
-        let mut updated = false;
-        if !mark_list.is_empty() {
-            for index in big_group {
-                if !small_group.contains(&index) {
-                    for number in &mark_list {
-                        let was_updated =
-                            self.little_boxes[index as usize].make_impossible(*number);
-                        if was_updated {
-                            updated = true;
-                        }
-                    }
-                }
-            }
-        }
-
-        // update times updated
-        if updated {
-            self.set_times_updated_plus_one();
-        }
-    }
-
     // find if main has any unique possibilities that sub1 or sub2 don't have
     pub fn main_vs_sub_unique_possibilities(main: &[i32], sub1: &[i32], sub2: &[i32]) -> Vec<i32> {
         let mut row_col_unique: Vec<i32> = Vec::new();
 
         for possibility in main {
-            if !sub1.contains(&possibility) && !sub2.contains(&possibility) {
+            if !sub1.contains(possibility) && !sub2.contains(possibility) {
                 row_col_unique.push(*possibility);
             }
         }
@@ -703,229 +1128,147 @@ impl BigBox {
     }
 
     // get a Vec<Vec<u8>> of all groups indexes
-    pub fn get_all_groups_indexes() -> Vec<Vec<i32>> {
+    pub fn get_all_groups_indexes(&self) -> Vec<Vec<i32>> {
         let mut all_groups_indexes: Vec<Vec<i32>> = Vec::new();
 
-        for index in ROW_STARTERS.iter() {
-            all_groups_indexes.push(BigBox::get_row_indices(index));
-        }
-        for index in COL_STARTERS.iter() {
-            all_groups_indexes.push(BigBox::get_col_indices(index));
-        }
-        for index in BOX_STARTERS.iter() {
-            all_groups_indexes.push(BigBox::get_box_indices(index));
-        }
+        all_groups_indexes.append(&mut self.layout.rows());
+        all_groups_indexes.append(&mut self.layout.cols());
+        all_groups_indexes.append(&mut self.layout.boxes());
+        all_groups_indexes.append(&mut self.layout.extra_units());
 
         all_groups_indexes
     }
 
-    // this is a catch all for updating notes based on pairs/triples/etc
-    pub fn process_and_update_notes(&mut self) {
-        let all_groups = BigBox::get_all_groups_indexes();
-        // this'll be processed at the end
-        // in format <all_groups_index, affected_indexes, valid_notes>
-        let mut process_list: Vec<(i32, Vec<i32>, Vec<i32>)> = Vec::new();
-
-        for (all_groups_index, this_groups_indexes) in all_groups.iter().enumerate() {
-            // gets a list of all notes and their possibilities number
-            // format <note, number of times seen>
-            let mut group_posibilities_no: HashMap<i32, i32> = HashMap::new();
-
-            // now go through all of this_groups_indexes
-            for cell in this_groups_indexes {
-                for possibility in self.little_boxes[*cell as usize].get_possibles() {
-                    let count = group_posibilities_no.entry(possibility).or_insert(0);
-                    *count += 1;
-                }
-            }
+    // update with the scan_one_possible list
+    pub fn add_valid_values(&mut self, valid_values: Vec<(i32, i32)>) {
+        for (index, number) in valid_values {
+            self.update_little_box_value(&index, &number);
 
-            // got a list of notes and their number of possibilities, now it's time to
-            // process these to see if there are doubles, triples, or even quadruples
-            match BigBox::find_valid_multiples(2, &group_posibilities_no) {
-                Some(i) => match self.find_valid_indexes(&this_groups_indexes, &i, 2) {
-                    Some(n) => {
-                        process_list.push((all_groups_index as i32, n, i));
-                    }
-                    None => {}
-                },
-                None => {}
+            // now update the affected rows
+            let affected_list = self.get_all_affected_indices(index);
+            for i in affected_list {
+                self.little_boxes[i as usize].make_impossible(number);
             }
         }
+    }
 
-        let mut note_updated = false;
+    // is every cell filled in
+    pub fn is_solved(&self) -> bool {
+        self.little_boxes
+            .iter()
+            .all(|little_box| matches!(little_box.get_value(), Some(i) if self.layout.is_number(i)))
+    }
 
-        for (all_groups_index, affected_indexes, valid_notes) in process_list {
-            for cell in &all_groups[all_groups_index as usize] {
-                if affected_indexes.contains(cell) {
-                    for note in ALL_NOTES_POSSIBILITIES.iter() {
-                        if !valid_notes.contains(note)
-                            && self.little_boxes[*cell as usize].make_impossible(*note)
-                        {
-                            note_updated = true;
-                        }
-                    }
-                } else {
-                    for note in ALL_NOTES_POSSIBILITIES.iter() {
-                        if valid_notes.contains(note)
-                            && self.little_boxes[*cell as usize].make_impossible(*note)
-                        {
-                            note_updated = true;
-                        }
-                    }
-                }
+    // put a step from a technique on the board, returns false if nothing changed
+    pub fn apply_step(&mut self, step: &Step) -> bool {
+        let mut updated = false;
+        for (index, number) in &step.eliminations {
+            if self.little_boxes[*index as usize].make_impossible(*number) {
+                updated = true;
             }
         }
-
-        if note_updated {
+        if updated {
             self.set_times_updated_plus_one();
         }
-    }
-
-    pub fn find_valid_multiples(
-        type_of_multiple: i32,
-        group_posibilities_no: &HashMap<i32, i32>,
-    ) -> Option<Vec<i32>> {
-        let mut return_notes: Vec<i32> = Vec::new();
-        for (key, no_of_possibilities) in group_posibilities_no {
-            if no_of_possibilities == &type_of_multiple {
-                return_notes.push(*key);
-            }
-        }
 
-        if return_notes.len() == type_of_multiple as usize {
-            Some(return_notes)
-        } else {
-            None
-        }
-    }
-
-    // take self, a group I'm evaluating, and the notes in question in format
-    // <self, indexes, notes>
-    pub fn find_valid_indexes(
-        &self,
-        this_groups_indexes: &[i32],
-        notes: &[i32],
-        type_of_multiple: i32,
-    ) -> Option<Vec<i32>> {
-        let mut return_indexes: Vec<i32> = Vec::new();
-        for cell in this_groups_indexes {
-            let cells_possibilities = self.little_boxes[*cell as usize].get_possibles();
-            if !cells_possibilities.is_empty() {
-                let mut possibility_count = 0;
-                for possibility in &cells_possibilities {
-                    if notes.contains(&possibility) {
-                        possibility_count += 1;
-                    }
-                }
-
-                if possibility_count == notes.len() {
-                    return_indexes.push(*cell);
-                }
-            }
-        }
+        let times_updated = self.get_times_updated();
+        self.add_valid_values(step.placements.clone());
 
-        if return_indexes.len() == type_of_multiple as usize {
-            Some(return_indexes)
-        } else {
-            None
-        }
+        updated || times_updated != self.get_times_updated()
     }
 
-    pub fn process_obvious_pairs(&mut self) {
-        let all_groups_indexes = BigBox::get_all_groups_indexes();
-        // get all groups and start running through them to see if there are pairs of doubles
-
-        // this is a vector for a final run. it keeps an index of all_groups_indexes
-        // and the valid pair that can be run on the big board's subgroup.
-        let mut valid_obvious_pair_and_group: Vec<(i32, Vec<i32>)> = Vec::new();
-
-        for (index, group) in all_groups_indexes.iter().enumerate() {
-            let mut hash_count: HashMap<Vec<i32>, i32> = HashMap::new();
-
-            // go through the cells in the group, add them to the hashmap
-            for cell in group {
-                let possibilities = self.little_boxes[*cell as usize].get_possibles();
-                if possibilities.len() == 2 {
-                    let entry = hash_count.entry(possibilities).or_insert(0);
-                    *entry += 1;
-                }
-            }
-
-            // now each cell with two possibilities is in the hashmap, now look for a pair
-            for (key, value) in hash_count {
-                if value == 2 {
-                    valid_obvious_pair_and_group.push((index as i32, key));
-                }
-            }
-        }
+    pub fn solve_puzzle(&mut self) -> Vec<Step> {
+        self.solve_puzzle_with(&Pipeline::standard())
+    }
 
-        for (index, valid_pair) in valid_obvious_pair_and_group.iter() {
-            for cell in &all_groups_indexes[*index as usize] {
-                if *valid_pair != self.little_boxes[*cell as usize].get_possibles()
-                    && self.little_boxes[*cell as usize].make_many_impossible(valid_pair.to_vec())
-                {
-                    self.set_times_updated_plus_one();
-                }
-            }
-        }
+    // solve using your own set of techniques, returns the steps that were used
+    pub fn solve_puzzle_with(&mut self, pipeline: &Pipeline) -> Vec<Step> {
+        self.mark_impossible(self.scan_for_make_impossible());
+        pipeline.run(self)
     }
+}
 
-    // update with the scan_one_possible list
-    pub fn add_valid_values(&mut self, valid_values: Vec<(i32, i32)>) {
-        for (index, number) in valid_values {
-            self.update_little_box_value(&index, &number);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::PUZZLE;
+
+    #[test]
+    fn pasted_lines_and_grids() {
+        assert_eq!(read_pasted_board(PUZZLE).unwrap().to_string(), PUZZLE);
+        let grid = PUZZLE.parse::<BigBox>().unwrap().to_grid(GridStyle::Ascii);
+        assert_eq!(read_pasted_board(&grid).unwrap().to_string(), PUZZLE);
+        assert!(read_pasted_board(&".".repeat(17)).is_err());
+
+        let small = read_pasted_board("1.34.412214.43.1").unwrap();
+        assert_eq!(small.layout().size(), 4);
+        let big = Layout::for_size(16).unwrap();
+        let grid = BigBox::with_layout(big.clone(), vec![0; 256])
+            .unwrap()
+            .to_grid(GridStyle::Ascii);
+        assert_eq!(read_pasted_board(&grid).unwrap().layout(), &big);
+    }
 
-            // now update the affected rows
-            let affected_list = BigBox::get_all_affected_indices(index);
-            for i in affected_list {
-                self.little_boxes[i as usize].make_impossible(number);
-            }
-        }
+    #[test]
+    fn typing_into_a_cell() {
+        assert_eq!(typed_number("5", 9), Some(5));
+        assert_eq!(typed_number("A", 9), None);
+        assert_eq!(typed_number("a", 16), Some(10));
+        assert_eq!(typed_number("12", 16), None);
+        assert_eq!(typed_number("", 9), None);
     }
 
-    pub fn solve_puzzle(&mut self) {
-        self.mark_impossible(self.scan_for_make_impossible());
+    #[test]
+    fn pasted_pencilmarks_with_a_square_number_of_digits() {
+        // 47 cells with 2 notes and 34 with 3 is 196 digits, which would be a
+        // 14x14 board as a line
+        let cells: Vec<&str> = (0..81)
+            .map(|index| if index < 47 { "12" } else { "123" })
+            .collect();
+        let grid: String = cells.chunks(9).map(|row| row.join(" ") + "\n").collect();
+        let board = read_pasted_board(&grid).unwrap();
+        assert_eq!(board.layout().size(), 9);
+        assert_eq!(board.little_boxes[0].get_possibles(), vec![1, 2]);
+        assert_eq!(board.little_boxes[80].get_possibles(), vec![1, 2, 3]);
+    }
 
-        loop {
-            let update_list = self.scan_one_possible();
-            let total_times_updated = self.get_times_updated();
-            if !update_list.is_empty() {
-                self.add_valid_values(update_list);
-            }
+    #[test]
+    fn changing_the_layout_keeps_the_notes() {
+        let mut board: BigBox = PUZZLE.parse().unwrap();
+        board.mark_impossible(vec![(0, 4), (0, 5)]);
+        board.update_little_box_value(&1, &5);
+        assert_eq!(board.get_little_box_value(&1), Some(5));
+
+        let diagonal = board
+            .with_layout_keeping_notes(Layout::standard().with_diagonals())
+            .unwrap();
+        assert!(diagonal.layout().has_diagonals());
+        assert_eq!(diagonal.get_values(), board.get_values());
+        assert_eq!(diagonal.get_givens(), board.get_givens());
+        assert_eq!(
+            diagonal.little_boxes[0].get_possibles(),
+            board.little_boxes[0].get_possibles()
+        );
+        assert!(!diagonal.little_boxes[1].is_given());
 
-            for i in 0..=80 {
-                match self.little_boxes[i].get_value() {
-                    Some(i) if i == 0 => {
-                        self.scan_for_one_possible_in_group(i as i32);
-                    },
-                    _ => ()
-                }
-            }
+        assert!(board
+            .with_layout_keeping_notes(Layout::for_size(4).unwrap())
+            .is_none());
+    }
 
-            self.find_medium_box_row_col_unique_possibles(MEDIUM_BOX_1);
-            self.find_medium_box_row_col_unique_possibles(MEDIUM_BOX_2);
-            self.find_medium_box_row_col_unique_possibles(MEDIUM_BOX_3);
-            self.find_medium_box_row_col_unique_possibles(MEDIUM_BOX_4);
-            self.find_medium_box_row_col_unique_possibles(MEDIUM_BOX_5);
-            self.find_medium_box_row_col_unique_possibles(MEDIUM_BOX_6);
-            self.find_medium_box_row_col_unique_possibles(MEDIUM_BOX_7);
-            self.find_medium_box_row_col_unique_possibles(MEDIUM_BOX_8);
-            self.find_medium_box_row_col_unique_possibles(MEDIUM_BOX_9);
-
-            // this should happen if the board wasn't updated.
-            if total_times_updated == self.get_times_updated() {
-                self.process_and_update_notes();
-            }
+    #[test]
+    fn placed_values_are_not_givens() {
+        let mut board: BigBox = PUZZLE.parse().unwrap();
+        assert!(board.little_boxes[2].is_given());
 
-            // this should happen if the previous notes check failed
-            if total_times_updated == self.get_times_updated() {
-                self.process_obvious_pairs();
-            }
+        board.update_little_box_value(&2, &0);
+        board.update_little_box_value(&2, &4);
+        assert_eq!(board.get_little_box_value(&2), Some(4));
+        assert!(!board.little_boxes[2].is_given());
+        assert_eq!(board.get_givens()[2], 0);
 
-            // check again if notes weren't updated
-            if total_times_updated == self.get_times_updated() {
-                break;
-            }
-        }
+        board.update_little_box_value(&0, &5);
+        assert_eq!(board.get_little_box_value(&0), Some(5));
+        assert!(!board.little_boxes[0].is_given());
     }
 }
@@ -0,0 +1,394 @@
+// variants drawn as lines through the cells:
+// - thermometers, the numbers go up from the bulb to the other end
+// - arrows, the number in the circle is the sum of the numbers along the arrow.
+//   they can repeat, unless the cells are peers
+// - german whispers, cells next to each other on the line are at least 5 apart
+//   (half the board size, rounded up)
+//
+// thermometers and whispers are rules between two cells, so they're checked
+// like the other ones in relations.rs. arrows are sums like a killer's cages,
+// only the sum is whatever goes in the circle.
+//
+// lines are written one to a line, the kind and then the cells in order, like
+// `thermo: r1c1 r1c2 r2c3`. an arrow starts with its circle.
+
+use crate::killer::{fitting_notes, parse_cell};
+use crate::relations::{options, relation_step, Relation};
+use crate::{BigBox, Layout, Step, Technique};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineKind {
+    Thermometer,
+    Arrow,
+    Whisper,
+}
+
+impl LineKind {
+    pub const ALL: [LineKind; 3] = [LineKind::Thermometer, LineKind::Arrow, LineKind::Whisper];
+
+    // how the kind of line is written in text
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineKind::Thermometer => "thermo",
+            LineKind::Arrow => "arrow",
+            LineKind::Whisper => "whisper",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Line {
+    pub kind: LineKind,
+    // in order along the line, starting from a thermometer's bulb or an
+    // arrow's circle
+    pub cells: Vec<i32>,
+}
+
+impl Line {
+    pub fn new(kind: LineKind, cells: Vec<i32>) -> Line {
+        Line { kind, cells }
+    }
+}
+
+// read lines written one to a line as `kind: r1c1 r1c2 ...`, the kind is
+// thermo, arrow or whisper. blank lines are skipped
+pub fn parse_lines(layout: &Layout, text: &str) -> Result<Vec<Line>, String> {
+    let mut lines: Vec<Line> = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let mut parts = line.splitn(2, ':');
+        let name = parts.next().unwrap_or("").trim().to_lowercase();
+        let kind = LineKind::ALL
+            .iter()
+            .find(|kind| kind.name() == name)
+            .ok_or_else(|| format!("{} isn't a line, they're thermo, arrow or whisper", name))?;
+        let cells = parts
+            .next()
+            .ok_or_else(|| format!("line doesn't have a : after its kind: {}", line))?
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|cell| !cell.is_empty())
+            .map(|cell| parse_cell(layout, cell))
+            .collect::<Result<Vec<i32>, String>>()?;
+        lines.push(Line::new(*kind, cells));
+    }
+    Ok(lines)
+}
+
+// the lines as text, the way parse_lines reads them
+pub fn write_lines(layout: &Layout) -> String {
+    layout
+        .lines()
+        .iter()
+        .map(|line| {
+            let cells: Vec<String> = line
+                .cells
+                .iter()
+                .map(|cell| format!("r{}c{}", layout.row(*cell) + 1, layout.col(*cell) + 1))
+                .collect();
+            format!("{}: {}\n", line.kind.name(), cells.join(" "))
+        })
+        .collect()
+}
+
+// the cells next to a cell along every line of one kind, and whether they come
+// before it
+fn along(layout: &Layout, kind: LineKind, index: i32) -> Vec<(i32, bool)> {
+    let mut next_to: Vec<(i32, bool)> = Vec::new();
+    for line in layout.lines().iter().filter(|line| line.kind == kind) {
+        for (position, cell) in line.cells.iter().enumerate() {
+            if *cell != index {
+                continue;
+            }
+            if position > 0 {
+                next_to.push((line.cells[position - 1], true));
+            }
+            if position + 1 < line.cells.len() {
+                next_to.push((line.cells[position + 1], false));
+            }
+        }
+    }
+    next_to
+}
+
+// a cell is bigger than the one before it on a thermometer, and smaller than
+// the one after
+pub(crate) fn thermometer_relations(layout: &Layout, index: i32) -> Vec<(i32, Relation)> {
+    along(layout, LineKind::Thermometer, index)
+        .into_iter()
+        .map(|(other, before)| {
+            (
+                other,
+                if before {
+                    Relation::More
+                } else {
+                    Relation::Less
+                },
+            )
+        })
+        .collect()
+}
+
+// cells next to each other on a whisper are far apart
+pub(crate) fn whisper_relations(layout: &Layout, index: i32) -> Vec<(i32, Relation)> {
+    let gap = (layout.size() + 1) / 2;
+    along(layout, LineKind::Whisper, index)
+        .into_iter()
+        .map(|(other, _)| (other, Relation::FarApart(gap)))
+        .collect()
+}
+
+// x and y, in cells from the top left corner of the board
+pub(crate) type Point = (f64, f64);
+
+// the middle of a cell
+pub(crate) fn cell_center(layout: &Layout, index: i32) -> Point {
+    (
+        layout.col(index) as f64 + 0.5,
+        layout.row(index) as f64 + 0.5,
+    )
+}
+
+// where an arrow is drawn: the points from the edge of its circle (`radius`
+// cells) through the middle of the rest of its cells, then the ends of the two
+// short lines that make its head, pointing back from the last cell
+pub(crate) fn arrow_shape(layout: &Layout, line: &Line, radius: f64) -> (Vec<Point>, [Point; 2]) {
+    let mut points: Vec<Point> = line
+        .cells
+        .iter()
+        .map(|cell| cell_center(layout, *cell))
+        .collect();
+    let ((x1, y1), (x2, y2)) = (points[0], points[1]);
+    let gap = radius / ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+    points[0] = (x1 + (x2 - x1) * gap, y1 + (y2 - y1) * gap);
+
+    let ((x1, y1), (x2, y2)) = (points[points.len() - 2], points[points.len() - 1]);
+    let angle = (y2 - y1).atan2(x2 - x1);
+    let head = |side: f64| {
+        (
+            x2 - (angle + side).cos() * 0.2,
+            y2 - (angle + side).sin() * 0.2,
+        )
+    };
+    (points, [head(-0.5), head(0.5)])
+}
+
+fn has_lines(board: &BigBox, kind: LineKind) -> bool {
+    board.layout().lines().iter().any(|line| line.kind == kind)
+}
+
+// notes on a thermometer that don't leave room for a smaller number before them
+// or a bigger one after
+pub struct Thermometer;
+
+impl Technique for Thermometer {
+    fn name(&self) -> &str {
+        "Thermometer"
+    }
+
+    fn difficulty(&self) -> f32 {
+        1.8
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        if !has_lines(board, LineKind::Thermometer) {
+            return None;
+        }
+        relation_step(self, board, thermometer_relations)
+    }
+}
+
+// notes on a whisper line that are too close to everything the cells next to
+// them on the line can still be
+pub struct GermanWhispers;
+
+impl Technique for GermanWhispers {
+    fn name(&self) -> &str {
+        "German Whispers"
+    }
+
+    fn difficulty(&self) -> f32 {
+        1.8
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        if !has_lines(board, LineKind::Whisper) {
+            return None;
+        }
+        relation_step(self, board, whisper_relations)
+    }
+}
+
+// numbers in an arrow's circle that nothing along the arrow adds up to, and
+// notes along the arrow that can't add up to anything left in the circle
+pub struct Arrow;
+
+impl Technique for Arrow {
+    fn name(&self) -> &str {
+        "Arrow"
+    }
+
+    fn difficulty(&self) -> f32 {
+        2.0
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        for line in board
+            .layout()
+            .lines()
+            .iter()
+            .filter(|line| line.kind == LineKind::Arrow)
+        {
+            let (circle, arrow) = (line.cells[0], &line.cells[1..]);
+            // a cleared cell without notes doesn't say anything about the rest
+            if line
+                .cells
+                .iter()
+                .any(|cell| options(board, *cell).is_empty())
+            {
+                continue;
+            }
+
+            // every number the circle can have that the arrow can add up to,
+            // and the notes along the arrow that go with one of them
+            let mut fitting_circle: Vec<i32> = Vec::new();
+            let mut fitting_arrow: Vec<Vec<i32>> = vec![Vec::new(); arrow.len()];
+            for sum in options(board, circle) {
+                let fitting = match fitting_notes(board, arrow, sum) {
+                    Some(fitting) if fitting.iter().all(|notes| !notes.is_empty()) => fitting,
+                    _ => continue,
+                };
+                fitting_circle.push(sum);
+                for (notes, more) in fitting_arrow.iter_mut().zip(fitting) {
+                    for note in more {
+                        if !notes.contains(&note) {
+                            notes.push(note);
+                        }
+                    }
+                }
+            }
+
+            let mut step = Step::new(self);
+            for (cell, notes) in line
+                .cells
+                .iter()
+                .zip(std::iter::once(&fitting_circle).chain(&fitting_arrow))
+            {
+                if board.little_boxes[*cell as usize].get_value().is_some() {
+                    continue;
+                }
+                for note in board.little_boxes[*cell as usize].get_possibles() {
+                    if !notes.contains(&note) {
+                        step.eliminations.push((*cell, note));
+                    }
+                }
+            }
+            if !step.is_empty() {
+                return Some(step);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{board_with, eliminations};
+
+    #[test]
+    fn thermometers_go_up() {
+        let lines = vec![Line::new(LineKind::Thermometer, vec![0, 1, 2])];
+        let board = board_with(Layout::standard().with_lines(lines).unwrap(), &[(1, 5)]);
+        // the bulb is smaller than 5 and the end is bigger, the first cell
+        // found with something to take out is the bulb's neighbour
+        assert_eq!(
+            eliminations(&Thermometer, &board),
+            vec![
+                (0, 5),
+                (0, 6),
+                (0, 7),
+                (0, 8),
+                (0, 9),
+                (2, 1),
+                (2, 2),
+                (2, 3),
+                (2, 4),
+                (2, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn whispers_are_far_apart() {
+        let lines = vec![Line::new(LineKind::Whisper, vec![0, 1])];
+        let board = board_with(Layout::standard().with_lines(lines).unwrap(), &[(0, 3)]);
+        assert_eq!(
+            eliminations(&GermanWhispers, &board),
+            vec![(1, 1), (1, 2), (1, 3), (1, 4), (1, 5), (1, 6), (1, 7)]
+        );
+    }
+
+    #[test]
+    fn arrows_add_up() {
+        let lines = vec![Line::new(LineKind::Arrow, vec![0, 1, 2])];
+        let board = board_with(Layout::standard().with_lines(lines).unwrap(), &[(0, 4)]);
+        // two different numbers that add up to 4 are 1 and 3
+        let mut wrong: Vec<(i32, i32)> = Vec::new();
+        for cell in [1, 2].iter() {
+            wrong.extend([2, 4, 5, 6, 7, 8, 9].iter().map(|number| (*cell, *number)));
+        }
+        assert_eq!(eliminations(&Arrow, &board), wrong);
+
+        // the circle can't be smaller than 1 + 2
+        let lines = vec![Line::new(LineKind::Arrow, vec![0, 1, 2])];
+        let board = board_with(Layout::standard().with_lines(lines).unwrap(), &[]);
+        assert_eq!(
+            eliminations(&Arrow, &board),
+            vec![(0, 1), (0, 2), (1, 9), (2, 9)]
+        );
+    }
+
+    #[test]
+    fn cleared_cells_take_nothing_out() {
+        for kind in LineKind::ALL.iter() {
+            let lines = vec![Line::new(*kind, vec![0, 1, 2])];
+            let mut board = board_with(Layout::standard().with_lines(lines).unwrap(), &[]);
+            board.little_boxes[0].update_value(0);
+            // the other two cells still work on each other, a thermometer
+            // takes the 1 out of the end, but nothing goes from the first two
+            let found = [&Thermometer as &dyn Technique, &GermanWhispers, &Arrow]
+                .iter()
+                .filter_map(|technique| technique.find(&board))
+                .flat_map(|step| step.eliminations)
+                .filter(|(cell, _)| *cell != 2)
+                .count();
+            assert_eq!(found, 0, "{}", kind.name());
+        }
+    }
+
+    #[test]
+    fn lines_round_trip() {
+        let layout = Layout::standard();
+        let text = "thermo: r1c1 r1c2 r2c3\narrow: r5c5 r5c6 r5c7\nwhisper: r9c1 r8c2\n";
+        let lines = parse_lines(&layout, text).unwrap();
+        assert_eq!(lines[0], Line::new(LineKind::Thermometer, vec![0, 1, 11]));
+        assert_eq!(
+            write_lines(&layout.clone().with_lines(lines).unwrap()),
+            text
+        );
+        assert!(parse_lines(&layout, "snake: r1c1 r1c2").is_err());
+        assert!(parse_lines(&layout, "thermo r1c1 r1c2").is_err());
+    }
+
+    #[test]
+    fn lines_have_to_join_up() {
+        let with = |kind, cells| Layout::standard().with_lines(vec![Line::new(kind, cells)]);
+        assert!(with(LineKind::Thermometer, vec![0]).is_err());
+        assert!(with(LineKind::Thermometer, (0..10).collect()).is_err());
+        assert!(with(LineKind::Whisper, vec![0, 2]).is_err());
+        assert!(with(LineKind::Whisper, vec![0, 1, 0]).is_err());
+        assert!(with(LineKind::Whisper, vec![80, 81]).is_err());
+        assert!(with(LineKind::Arrow, vec![0, 10]).is_ok());
+    }
+}
@@ -0,0 +1,368 @@
+// puzzle collections from the OpenSudoku android app. the collection's details
+// are elements inside the root and every puzzle is a game with 81 numbers, 0
+// for empty cells:
+//
+//     <opensudoku>
+//       <name>Easy</name>
+//       <author>someone</author>
+//       <game data="000302600900..."/>
+//       ...
+//     </opensudoku>
+//
+// the newer export puts the games in folders, `<folder name="Easy">`, each of
+// which is read as its own collection. only the clues are read and written, and
+// only 9x9 puzzles fit.
+//
+// there's no xml library, the files are simple enough that the little reader
+// at the bottom handles them.
+
+use crate::text::{check_nine_by_nine, parse_nine_by_nine};
+use crate::BigBox;
+
+#[derive(Debug, Clone)]
+pub struct OpenSudokuFolder {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub comment: Option<String>,
+    pub created: Option<String>,
+    pub source: Option<String>,
+    pub level: Option<String>,
+    pub source_url: Option<String>,
+    pub puzzles: Vec<BigBox>,
+}
+
+impl OpenSudokuFolder {
+    // an empty collection with no details
+    pub fn new() -> OpenSudokuFolder {
+        OpenSudokuFolder {
+            name: None,
+            author: None,
+            description: None,
+            comment: None,
+            created: None,
+            source: None,
+            level: None,
+            source_url: None,
+            puzzles: Vec::new(),
+        }
+    }
+
+    fn detail_mut(&mut self, element: &str) -> Option<&mut Option<String>> {
+        match element {
+            "name" => Some(&mut self.name),
+            "author" => Some(&mut self.author),
+            "description" => Some(&mut self.description),
+            "comment" => Some(&mut self.comment),
+            "created" => Some(&mut self.created),
+            "source" => Some(&mut self.source),
+            "level" => Some(&mut self.level),
+            "sourceURL" => Some(&mut self.source_url),
+            _ => None,
+        }
+    }
+
+    fn details(&self) -> Vec<(&str, &String)> {
+        let known = [
+            ("name", &self.name),
+            ("author", &self.author),
+            ("description", &self.description),
+            ("comment", &self.comment),
+            ("created", &self.created),
+            ("source", &self.source),
+            ("level", &self.level),
+            ("sourceURL", &self.source_url),
+        ];
+
+        known
+            .iter()
+            .filter_map(|(element, value)| value.as_ref().map(|value| (*element, value)))
+            .collect()
+    }
+}
+
+impl Default for OpenSudokuFolder {
+    fn default() -> OpenSudokuFolder {
+        OpenSudokuFolder::new()
+    }
+}
+
+// every collection in the file. games that aren't in a folder go in one
+// collection with the details from the root
+pub fn read_opensudoku(text: &str) -> Result<Vec<OpenSudokuFolder>, String> {
+    let tokens = read_xml(text)?;
+
+    let mut root = OpenSudokuFolder::new();
+    let mut folders: Vec<OpenSudokuFolder> = Vec::new();
+    let mut in_folder = false;
+    // the elements we're inside of, outermost first
+    let mut open: Vec<String> = Vec::new();
+
+    for token in tokens {
+        match token {
+            Xml::Start(element, attributes, self_closing) => {
+                if open.is_empty() && element != "opensudoku" {
+                    return Err(format!("expected <opensudoku>, found <{}>", element));
+                }
+
+                match element.as_str() {
+                    "folder" => {
+                        let mut folder = OpenSudokuFolder::new();
+                        for (attribute, value) in attributes {
+                            if let Some(detail) = folder.detail_mut(&attribute) {
+                                *detail = Some(value);
+                            }
+                        }
+                        folders.push(folder);
+                        in_folder = !self_closing;
+                    }
+                    "game" => {
+                        let data = attributes
+                            .iter()
+                            .find(|(attribute, _)| attribute == "data")
+                            .map(|(_, value)| value)
+                            .ok_or("<game> doesn't have any data")?;
+                        let board = parse_nine_by_nine(data)?;
+                        match folders.last_mut() {
+                            Some(folder) if in_folder => folder.puzzles.push(board),
+                            _ => root.puzzles.push(board),
+                        }
+                    }
+                    _ => (),
+                }
+
+                if !self_closing {
+                    open.push(element);
+                }
+            }
+            Xml::End(element) => {
+                if open.pop().as_ref() != Some(&element) {
+                    return Err(format!(
+                        "</{}> doesn't match the element it closes",
+                        element
+                    ));
+                }
+                if element == "folder" {
+                    in_folder = false;
+                }
+            }
+            Xml::Text(text) => {
+                // details only count directly inside the root or a folder
+                if let [.., parent, element] = open.as_slice() {
+                    let folder = if parent == "folder" {
+                        folders.last_mut()
+                    } else if parent == "opensudoku" {
+                        Some(&mut root)
+                    } else {
+                        None
+                    };
+                    if let Some(detail) = folder.and_then(|folder| folder.detail_mut(element)) {
+                        detail.get_or_insert_with(String::new).push_str(text.trim());
+                    }
+                }
+            }
+        }
+    }
+
+    if !open.is_empty() {
+        return Err(format!("<{}> is never closed", open[open.len() - 1]));
+    }
+    if root.details().is_empty() && root.puzzles.is_empty() && !folders.is_empty() {
+        return Ok(folders);
+    }
+
+    let mut collections = vec![root];
+    collections.append(&mut folders);
+    Ok(collections)
+}
+
+// the older format, which every version of the app can import. an error if
+// one of the puzzles isn't 9x9
+pub fn write_opensudoku(folder: &OpenSudokuFolder) -> Result<String, String> {
+    let mut text = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opensudoku>\n");
+    for (element, value) in folder.details() {
+        text.push_str(&format!("  <{}>{}</{}>\n", element, escape(value), element));
+    }
+
+    for board in &folder.puzzles {
+        check_nine_by_nine(board)?;
+        let data: String = board
+            .get_givens()
+            .iter()
+            .map(|value| value.to_string())
+            .collect();
+        text.push_str(&format!("  <game data=\"{}\"/>\n", data));
+    }
+
+    text.push_str("</opensudoku>\n");
+    Ok(text)
+}
+
+// the pieces of an xml file. a start tag has its attributes and whether it
+// closes itself
+enum Xml {
+    Start(String, Vec<(String, String)>, bool),
+    End(String),
+    Text(String),
+}
+
+fn read_xml(text: &str) -> Result<Vec<Xml>, String> {
+    let mut tokens: Vec<Xml> = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = skip_past(after, "-->")?;
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").ok_or("<![CDATA[ is never closed")?;
+            tokens.push(Xml::Text(after[..end].to_string()));
+            rest = &after[end + 3..];
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            rest = skip_past(rest, ">")?;
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').ok_or("closing tag is never finished")?;
+            tokens.push(Xml::End(after[..end].trim().to_string()));
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('<') {
+            let (tag, after) = read_tag(after)?;
+            tokens.push(tag);
+            rest = after;
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Xml::Text(unescape(&rest[..end])?));
+            rest = &rest[end..];
+        }
+    }
+
+    Ok(tokens)
+}
+
+// everything after the first `end`
+fn skip_past<'a>(text: &'a str, end: &str) -> Result<&'a str, String> {
+    text.find(end)
+        .map(|position| &text[position + end.len()..])
+        .ok_or_else(|| format!("expected '{}' before the end of the file", end))
+}
+
+// a start tag, from just after the `<` to just after the `>`
+fn read_tag(text: &str) -> Result<(Xml, &str), String> {
+    let name_end = text
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .ok_or("tag is never finished")?;
+    let name = text[..name_end].to_string();
+    let mut attributes: Vec<(String, String)> = Vec::new();
+    let mut rest = text[name_end..].trim_start();
+
+    loop {
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Ok((Xml::Start(name, attributes, true), after));
+        } else if let Some(after) = rest.strip_prefix('>') {
+            return Ok((Xml::Start(name, attributes, false), after));
+        }
+
+        let equals = rest
+            .find('=')
+            .ok_or_else(|| format!("bad attribute in <{}>", name))?;
+        let attribute = rest[..equals].trim().to_string();
+        let after = rest[equals + 1..].trim_start();
+        let quote = after
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("attribute {} in <{}> isn't quoted", attribute, name))?;
+        let end = after[1..]
+            .find(quote)
+            .ok_or_else(|| format!("attribute {} in <{}> is never closed", attribute, name))?;
+        attributes.push((attribute, unescape(&after[1..end + 1])?));
+        rest = after[end + 2..].trim_start();
+    }
+}
+
+fn unescape(text: &str) -> Result<String, String> {
+    let mut unescaped = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..].find(';').ok_or("& without a ; after it")? + start;
+        let entity = &rest[start + 1..end];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(decimal) = entity.strip_prefix('#') {
+                    decimal.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(std::char::from_u32)
+                    .ok_or_else(|| format!("unknown entity &{};", entity))?
+            }
+        };
+        unescaped.push(c);
+        rest = &rest[end + 1..];
+    }
+
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME: &str =
+        "003020600900305001001806400008102900700000008006708200002609500800203009005010300";
+
+    #[test]
+    fn round_trip() {
+        let mut folder = OpenSudokuFolder::new();
+        folder.name = Some("Easy & quick".to_string());
+        folder.puzzles.push(GAME.parse().unwrap());
+
+        let text = write_opensudoku(&folder).unwrap();
+        assert!(text.contains("<name>Easy &amp; quick</name>"));
+        let read = read_opensudoku(&text).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].name, folder.name);
+        assert_eq!(
+            read[0].puzzles[0].get_values(),
+            folder.puzzles[0].get_values()
+        );
+    }
+
+    #[test]
+    fn folders_are_collections() {
+        let text = format!(
+            "<opensudoku><folder name=\"A\"><game data=\"{}\"/></folder><folder name=\"B\"/></opensudoku>",
+            GAME
+        );
+        let read = read_opensudoku(&text).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].name.as_deref(), Some("A"));
+        assert_eq!(read[0].puzzles.len(), 1);
+        assert!(read[1].puzzles.is_empty());
+    }
+
+    #[test]
+    fn other_sizes_are_errors() {
+        let mut folder = OpenSudokuFolder::new();
+        folder.puzzles.push("1.3..4.1.1..3.2.".parse().unwrap());
+        assert!(write_opensudoku(&folder).is_err());
+        assert!(
+            read_opensudoku("<opensudoku><game data=\"1.3..4.1.1..3.2.\"/></opensudoku>").is_err()
+        );
+    }
+}
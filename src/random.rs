@@ -0,0 +1,76 @@
+// a small random number generator (xorshift64*), so we don't need `rand` and
+// `getrandom` in the wasm build. it's not good enough for anything secure,
+// but it's plenty for shuffling sudoku boards.
+
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        // run the seed through splitmix64 so small seeds (0, 1, 2...) still start
+        // somewhere random looking, and make sure the state is never 0
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        Random {
+            state: if z == 0 { 0x2545_F491_4F6C_DD1D } else { z },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // a number from 0 up to (but not including) max
+    pub fn below(&mut self, max: usize) -> usize {
+        if max == 0 {
+            return 0;
+        }
+        (self.next_u64() % max as u64) as usize
+    }
+
+    // fisher-yates shuffle
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Random::new(42);
+        let mut b = Random::new(42);
+        let mut c = Random::new(43);
+        let from_a: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+        let from_b: Vec<u64> = (0..10).map(|_| b.next_u64()).collect();
+        let from_c: Vec<u64> = (0..10).map(|_| c.next_u64()).collect();
+        assert_eq!(from_a, from_b);
+        assert_ne!(from_a, from_c);
+    }
+
+    #[test]
+    fn shuffling_keeps_everything() {
+        let mut random = Random::new(0);
+        let mut items: Vec<i32> = (0..20).collect();
+        random.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<i32>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<i32>>());
+
+        assert!((0..100).all(|_| random.below(7) < 7));
+        assert_eq!(random.below(0), 0);
+    }
+}
@@ -0,0 +1,180 @@
+// rates how hard a puzzle is by solving it with the easiest techniques first,
+// the same idea as the Sudoku Explainer ratings: the score of a puzzle is the
+// score of the hardest step that was needed to solve it.
+
+use crate::{BigBox, Pipeline};
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    // which band a score falls into
+    pub fn from_score(score: f32) -> Difficulty {
+        if score <= 1.5 {
+            Difficulty::Easy
+        } else if score <= 2.8 {
+            Difficulty::Medium
+        } else if score <= 4.0 {
+            Difficulty::Hard
+        } else {
+            Difficulty::Expert
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// parses the names Display gives, in any case
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Difficulty, String> {
+        Difficulty::ALL
+            .iter()
+            .find(|difficulty| difficulty.to_string().eq_ignore_ascii_case(s.trim()))
+            .copied()
+            .ok_or_else(|| format!("unknown difficulty: {}", s))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rating {
+    // score of the hardest step, 0.0 if there was nothing to do
+    pub score: f32,
+    pub hardest_technique: Option<String>,
+    // how many times each technique was used, easiest first
+    pub technique_counts: Vec<(String, u32)>,
+    // false if the techniques couldn't finish the puzzle
+    pub solved: bool,
+}
+
+impl Rating {
+    // None if the puzzle couldn't be solved without guessing
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        if self.solved {
+            Some(Difficulty::from_score(self.score))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.difficulty() {
+            Some(difficulty) => write!(f, "{} ({:.1})", difficulty, self.score),
+            None => write!(f, "Too hard (> {:.1})", self.score),
+        }
+    }
+}
+
+// rate a puzzle with all the techniques in this crate, easiest first
+pub fn rate_puzzle(board: &BigBox) -> Rating {
+    rate_puzzle_with(board, &Pipeline::standard())
+}
+
+// rate a puzzle with your own techniques, they're used in the order of the pipeline
+pub fn rate_puzzle_with(board: &BigBox, pipeline: &Pipeline) -> Rating {
+    let mut board = board.clone();
+    let steps = board.solve_puzzle_with(pipeline);
+
+    let mut rating = Rating {
+        score: 0.0,
+        hardest_technique: None,
+        technique_counts: Vec::new(),
+        solved: board.is_solved(),
+    };
+
+    for step in &steps {
+        if rating.hardest_technique.is_none() || step.difficulty > rating.score {
+            rating.score = step.difficulty;
+            rating.hardest_technique = Some(step.technique.clone());
+        }
+
+        match rating
+            .technique_counts
+            .iter_mut()
+            .find(|(name, _)| *name == step.technique)
+        {
+            Some((_, count)) => *count += 1,
+            None => rating.technique_counts.push((step.technique.clone(), 1)),
+        }
+    }
+
+    rating.technique_counts.sort_by(|(a, _), (b, _)| {
+        let difficulty = |name: &String| {
+            steps
+                .iter()
+                .find(|step| step.technique == *name)
+                .map(|step| step.difficulty)
+                .unwrap_or(0.0)
+        };
+        difficulty(a)
+            .partial_cmp(&difficulty(b))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    rating
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::PUZZLE;
+
+    #[test]
+    fn singles_are_easy() {
+        let rating = rate_puzzle(&PUZZLE.parse().unwrap());
+        assert!(rating.solved);
+        assert_eq!(rating.score, 1.5);
+        assert_eq!(rating.hardest_technique.as_deref(), Some("Hidden Single"));
+        assert_eq!(rating.difficulty(), Some(Difficulty::Easy));
+        assert_eq!(rating.to_string(), "Easy (1.5)");
+    }
+
+    #[test]
+    fn nothing_to_go_on() {
+        let rating = rate_puzzle(&BigBox::new(vec![0; 81]).unwrap());
+        assert!(!rating.solved);
+        assert_eq!(rating.difficulty(), None);
+        assert!(rating.technique_counts.is_empty());
+    }
+
+    #[test]
+    fn difficulty_names() {
+        for difficulty in Difficulty::ALL.iter() {
+            assert_eq!(
+                difficulty.to_string().to_lowercase().parse::<Difficulty>(),
+                Ok(*difficulty)
+            );
+        }
+        assert!("impossible".parse::<Difficulty>().is_err());
+        assert_eq!(Difficulty::from_score(2.6), Difficulty::Medium);
+        assert_eq!(Difficulty::from_score(4.2), Difficulty::Expert);
+    }
+}
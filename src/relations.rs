@@ -0,0 +1,392 @@
+// rules between two cells, rather than a whole group of them. on a
+// non-consecutive board, cells that touch (side by side, not diagonally) can't
+// have numbers that are next to each other, like 4 and 5.
+//
+// markers on the line between two cells work the same way:
+// - a white dot (kropki) means the numbers are one apart
+// - a black dot (kropki) means one number is twice the other
+// - an X means they add up to 10, a V that they add up to 5
+// with the negative constraint every pair that could have had a marker but
+// doesn't can't be like that either: no dot means not one apart and not
+// double, no X or V means not adding up to 10 or 5.
+//
+// thermometers and german whispers (see lines.rs) are rules between the cells
+// next to each other on the line.
+//
+// these don't make the cells peers, the cells can still have the same number,
+// so the usual techniques don't know about them. the techniques in here check
+// them a pair of cells at a time: a note can only stay if the other cell still
+// has a note (or its value) that goes with it. when a cell is filled in, that
+// takes the numbers that don't go with it out of the cells it has rules with.
+//
+// markers are written one to a line, the kind and then the two cells, like
+// `white: r1c1 r1c2` or `x: r4c5 r5c5`.
+
+use crate::killer::parse_cell;
+use crate::lines::{thermometer_relations, whisper_relations};
+use crate::{BigBox, Layout, Step, Technique};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Marker {
+    WhiteDot,
+    BlackDot,
+    X,
+    V,
+}
+
+impl Marker {
+    pub const ALL: [Marker; 4] = [Marker::WhiteDot, Marker::BlackDot, Marker::X, Marker::V];
+
+    // how the marker is written in text
+    pub fn name(&self) -> &'static str {
+        match self {
+            Marker::WhiteDot => "white",
+            Marker::BlackDot => "black",
+            Marker::X => "x",
+            Marker::V => "v",
+        }
+    }
+
+    pub fn is_kropki(&self) -> bool {
+        match self {
+            Marker::WhiteDot | Marker::BlackDot => true,
+            Marker::X | Marker::V => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Relation {
+    // the numbers are one apart
+    Consecutive,
+    // the numbers can't be one apart
+    NotConsecutive,
+    // one number is twice the other
+    Double,
+    NotDouble,
+    // the numbers add up to this
+    Sum(i32),
+    NotSum(i32),
+    // the first number is smaller, or bigger
+    Less,
+    More,
+    // the numbers are at least this far apart
+    FarApart(i32),
+}
+
+impl Relation {
+    // can one cell have `a` while the other has `b`
+    pub(crate) fn allows(&self, a: i32, b: i32) -> bool {
+        match self {
+            Relation::Consecutive => (a - b).abs() == 1,
+            Relation::NotConsecutive => (a - b).abs() != 1,
+            Relation::Double => a == b * 2 || b == a * 2,
+            Relation::NotDouble => a != b * 2 && b != a * 2,
+            Relation::Sum(sum) => a + b == *sum,
+            Relation::NotSum(sum) => a + b != *sum,
+            Relation::Less => a < b,
+            Relation::More => a > b,
+            Relation::FarApart(gap) => (a - b).abs() >= *gap,
+        }
+    }
+}
+
+// the rules of a non-consecutive board between a cell and the cells touching it
+fn non_consecutive_relations(layout: &Layout, index: i32) -> Vec<(i32, Relation)> {
+    if !layout.has_non_consecutive() {
+        return Vec::new();
+    }
+    layout
+        .neighbours(index)
+        .into_iter()
+        .map(|other| (other, Relation::NotConsecutive))
+        .collect()
+}
+
+// the rules from the kropki dots around a cell, and from the missing ones with
+// the negative constraint
+fn kropki_relations(layout: &Layout, index: i32) -> Vec<(i32, Relation)> {
+    let mut related: Vec<(i32, Relation)> = Vec::new();
+    for other in layout.neighbours(index) {
+        match layout.marker_between(index, other) {
+            Some(Marker::WhiteDot) => related.push((other, Relation::Consecutive)),
+            Some(Marker::BlackDot) => related.push((other, Relation::Double)),
+            _ if layout.has_negative_kropki() => {
+                related.push((other, Relation::NotConsecutive));
+                related.push((other, Relation::NotDouble));
+            }
+            _ => (),
+        }
+    }
+    related
+}
+
+// the rules from the Xs and Vs around a cell, and from the missing ones with the
+// negative constraint
+fn xv_relations(layout: &Layout, index: i32) -> Vec<(i32, Relation)> {
+    let mut related: Vec<(i32, Relation)> = Vec::new();
+    for other in layout.neighbours(index) {
+        match layout.marker_between(index, other) {
+            Some(Marker::X) => related.push((other, Relation::Sum(10))),
+            Some(Marker::V) => related.push((other, Relation::Sum(5))),
+            _ if layout.has_negative_xv() => {
+                related.push((other, Relation::NotSum(10)));
+                related.push((other, Relation::NotSum(5)));
+            }
+            _ => (),
+        }
+    }
+    related
+}
+
+// the cells a cell has a rule with, and the rule
+pub(crate) fn related_to(layout: &Layout, index: i32) -> Vec<(i32, Relation)> {
+    let mut related = non_consecutive_relations(layout, index);
+    related.append(&mut kropki_relations(layout, index));
+    related.append(&mut xv_relations(layout, index));
+    related.append(&mut thermometer_relations(layout, index));
+    related.append(&mut whisper_relations(layout, index));
+    related
+}
+
+// read markers written one to a line as `kind: r1c1 r1c2`, the kind is white,
+// black, x or v. blank lines are skipped
+pub fn parse_markers(layout: &Layout, text: &str) -> Result<Vec<(i32, i32, Marker)>, String> {
+    let mut markers: Vec<(i32, i32, Marker)> = Vec::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let mut parts = line.splitn(2, ':');
+        let name = parts.next().unwrap_or("").trim().to_lowercase();
+        let marker = Marker::ALL
+            .iter()
+            .find(|marker| marker.name() == name)
+            .ok_or_else(|| format!("{} isn't a marker, they're white, black, x or v", name))?;
+        let cells = parts
+            .next()
+            .ok_or_else(|| format!("marker doesn't have a : after its kind: {}", line))?
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|cell| !cell.is_empty())
+            .map(|cell| parse_cell(layout, cell))
+            .collect::<Result<Vec<i32>, String>>()?;
+        match cells[..] {
+            [a, b] => markers.push((a, b, *marker)),
+            _ => return Err(format!("a marker goes between two cells: {}", line)),
+        }
+    }
+    Ok(markers)
+}
+
+// the markers as text, the way parse_markers reads them
+pub fn write_markers(layout: &Layout) -> String {
+    let cell = |index: i32| format!("r{}c{}", layout.row(index) + 1, layout.col(index) + 1);
+    layout
+        .markers()
+        .iter()
+        .map(|(a, b, marker)| format!("{}: {} {}\n", marker.name(), cell(*a), cell(*b)))
+        .collect()
+}
+
+// the value of a filled in cell, or the notes of an empty one
+pub(crate) fn options(board: &BigBox, index: i32) -> Vec<i32> {
+    let little_box = &board.little_boxes[index as usize];
+    match little_box.get_value() {
+        Some(value) if board.layout().is_number(value) => vec![value],
+        _ => little_box.get_possibles(),
+    }
+}
+
+// a step taking out the notes, in the cells one cell has rules with, that
+// don't go with any of that cell's own notes. None if there aren't any. a cell
+// without a value or notes (like one that's been cleared) doesn't say anything
+// about the cells around it
+pub(crate) fn relation_step(
+    technique: &dyn Technique,
+    board: &BigBox,
+    relations: fn(&Layout, i32) -> Vec<(i32, Relation)>,
+) -> Option<Step> {
+    for index in 0..board.layout().cell_count() {
+        let own = options(board, index);
+        if own.is_empty() {
+            continue;
+        }
+        let mut step = Step::new(technique);
+        for (other, relation) in relations(board.layout(), index) {
+            if board.little_boxes[other as usize].get_value().is_some() {
+                continue;
+            }
+            for note in board.little_boxes[other as usize].get_possibles() {
+                if !own.iter().any(|value| relation.allows(*value, note))
+                    && !step.eliminations.contains(&(other, note))
+                {
+                    step.eliminations.push((other, note));
+                }
+            }
+        }
+        if !step.is_empty() {
+            return Some(step);
+        }
+    }
+    None
+}
+
+// notes next to a cell that are one away from everything it can still be
+pub struct NonConsecutive;
+
+impl Technique for NonConsecutive {
+    fn name(&self) -> &str {
+        "Non-Consecutive"
+    }
+
+    fn difficulty(&self) -> f32 {
+        1.7
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        if !board.layout().has_non_consecutive() {
+            return None;
+        }
+        relation_step(self, board, non_consecutive_relations)
+    }
+}
+
+// notes across a kropki dot (or a missing one, with the negative constraint)
+// that don't go with anything left on the other side
+pub struct Kropki;
+
+impl Technique for Kropki {
+    fn name(&self) -> &str {
+        "Kropki"
+    }
+
+    fn difficulty(&self) -> f32 {
+        1.8
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        let layout = board.layout();
+        if !layout.has_negative_kropki()
+            && !layout
+                .markers()
+                .iter()
+                .any(|(_, _, marker)| marker.is_kropki())
+        {
+            return None;
+        }
+        relation_step(self, board, kropki_relations)
+    }
+}
+
+// notes across an X or a V (or a missing one, with the negative constraint)
+// that don't add up with anything left on the other side
+pub struct Xv;
+
+impl Technique for Xv {
+    fn name(&self) -> &str {
+        "XV"
+    }
+
+    fn difficulty(&self) -> f32 {
+        1.8
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        let layout = board.layout();
+        if !layout.has_negative_xv()
+            && !layout
+                .markers()
+                .iter()
+                .any(|(_, _, marker)| !marker.is_kropki())
+        {
+            return None;
+        }
+        relation_step(self, board, xv_relations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{board_with, eliminations};
+
+    #[test]
+    fn non_consecutive_takes_out_the_numbers_one_away() {
+        let board = board_with(Layout::standard().with_non_consecutive(), &[(0, 5)]);
+        assert_eq!(
+            eliminations(&NonConsecutive, &board),
+            vec![(1, 4), (1, 6), (9, 4), (9, 6)]
+        );
+    }
+
+    #[test]
+    fn kropki_dots() {
+        let layout = Layout::standard()
+            .with_markers(vec![(0, 1, Marker::BlackDot)])
+            .unwrap();
+        let board = board_with(layout, &[(0, 3)]);
+        assert_eq!(
+            eliminations(&Kropki, &board),
+            vec![
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (1, 4),
+                (1, 5),
+                (1, 7),
+                (1, 8),
+                (1, 9)
+            ]
+        );
+
+        // with the negative constraint, no dot means not one apart or double
+        let layout = Layout::standard().with_negative_kropki();
+        let board = board_with(layout, &[(0, 4)]);
+        assert_eq!(
+            eliminations(&Kropki, &board),
+            vec![
+                (1, 2),
+                (1, 3),
+                (1, 5),
+                (1, 8),
+                (9, 2),
+                (9, 3),
+                (9, 5),
+                (9, 8)
+            ]
+        );
+    }
+
+    #[test]
+    fn xv_sums() {
+        let layout = Layout::standard()
+            .with_markers(vec![(0, 9, Marker::V)])
+            .unwrap();
+        let board = board_with(layout, &[(0, 1)]);
+        let wrong: Vec<(i32, i32)> = (1..=9)
+            .filter(|number| *number != 4)
+            .map(|number| (9, number))
+            .collect();
+        assert_eq!(eliminations(&Xv, &board), wrong);
+    }
+
+    #[test]
+    fn cleared_cells_take_nothing_out() {
+        // a cleared cell has the value 0 and no notes, which goes with nothing
+        let mut board = board_with(Layout::standard().with_non_consecutive(), &[]);
+        board.little_boxes[0].update_value(0);
+        assert_eq!(NonConsecutive.find(&board), None);
+    }
+
+    #[test]
+    fn markers_round_trip() {
+        let layout = Layout::standard();
+        let text = "white: r1c1 r1c2\nx: r4c5 r5c5\n";
+        let markers = parse_markers(&layout, text).unwrap();
+        assert_eq!(markers, vec![(0, 1, Marker::WhiteDot), (31, 40, Marker::X)]);
+        assert_eq!(
+            write_markers(&layout.clone().with_markers(markers).unwrap()),
+            text
+        );
+        assert!(parse_markers(&layout, "y: r1c1 r1c2").is_err());
+        assert!(parse_markers(&layout, "x: r1c1").is_err());
+    }
+}
@@ -0,0 +1,457 @@
+// draws a board as a standalone svg picture, for printing puzzles and for
+// showing solving steps on the tutorial pages.
+//
+// clues are black and numbers filled in afterwards are blue. with a step, the
+// cells it fills in are shaded green with the new number in them, the notes it
+// takes away are circled in red and its chain is drawn as arrows between notes.
+
+use crate::killer::{cage_label_cell, cage_outline};
+use crate::lines::{arrow_shape, cell_center};
+use crate::{BigBox, Layout, LineKind, Marker, Step};
+
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    // width and height of one cell, in pixels
+    pub cell_size: f64,
+    // draw the notes in empty cells
+    pub candidates: bool,
+    // indices of cells to shade yellow
+    pub highlighted: Vec<i32>,
+    pub step: Option<Step>,
+}
+
+impl SvgOptions {
+    // just the numbers, no notes or highlights
+    pub fn new() -> SvgOptions {
+        SvgOptions {
+            cell_size: 50.0,
+            candidates: false,
+            highlighted: Vec::new(),
+            step: None,
+        }
+    }
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions::new()
+    }
+}
+
+const HIGHLIGHT_COLOR: &str = "#fff3a0";
+const PLACEMENT_COLOR: &str = "#c8f0c8";
+const ELIMINATION_COLOR: &str = "#e01b24";
+const GIVEN_COLOR: &str = "#000000";
+const PLACED_COLOR: &str = "#1a5fb4";
+const CANDIDATE_COLOR: &str = "#666666";
+const LINK_COLOR: &str = "#e66100";
+const DIAGONAL_COLOR: &str = "#9a9996";
+const WINDOW_COLOR: &str = "#e4e4f4";
+const CAGE_COLOR: &str = "#3d3846";
+const THERMOMETER_COLOR: &str = "#c0bfbc";
+const ARROW_COLOR: &str = "#9a9996";
+const WHISPER_COLOR: &str = "#57e389";
+
+impl BigBox {
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let layout = self.layout();
+        let (box_width, box_height) = (layout.box_width(), layout.box_height());
+        let on_board = |index: &i32| (0..layout.cell_count()).contains(index);
+
+        let cell = options.cell_size;
+        // room around the grid so the thick outside lines aren't cut in half
+        let margin = cell / 10.0;
+        let size = cell * layout.size() as f64 + margin * 2.0;
+        // notes are laid out in the cell the same shape as a medium box, so on
+        // a normal board like a phone keypad
+        let note_size = cell / box_width.max(box_height) as f64;
+
+        // top left corner of a cell
+        let corner = |index: i32| {
+            (
+                margin + layout.col(index) as f64 * cell,
+                margin + layout.row(index) as f64 * cell,
+            )
+        };
+        // middle of where a note goes in its cell
+        let note_center = |index: i32, note: i32| {
+            let (x, y) = corner(index);
+            (
+                x + (((note - 1) % box_width) as f64 + 0.5) * cell / box_width as f64,
+                y + (((note - 1) / box_width) as f64 + 0.5) * cell / box_height as f64,
+            )
+        };
+
+        let (placements, eliminations, links) = match &options.step {
+            Some(step) => (
+                &step.placements[..],
+                &step.eliminations[..],
+                &step.links[..],
+            ),
+            None => (&[][..], &[][..], &[][..]),
+        };
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size:.1}\" height=\"{size:.1}\" viewBox=\"0 0 {size:.1} {size:.1}\">\n",
+            size = size
+        );
+        svg.push_str(&format!(
+            "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\"><path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"{}\"/></marker></defs>\n",
+            LINK_COLOR
+        ));
+        svg.push_str(&format!(
+            "<rect x=\"0\" y=\"0\" width=\"{size:.1}\" height=\"{size:.1}\" fill=\"#ffffff\"/>\n",
+            size = size
+        ));
+
+        // shading goes under everything else
+        let window_cells: Vec<i32> = layout.windows().concat();
+        let placed_cells: Vec<i32> = placements.iter().map(|(index, _)| *index).collect();
+        for (cells, color) in [
+            (&window_cells, WINDOW_COLOR),
+            (&options.highlighted, HIGHLIGHT_COLOR),
+            (&placed_cells, PLACEMENT_COLOR),
+        ]
+        .iter()
+        {
+            for index in cells.iter().filter(|index| on_board(index)) {
+                let (x, y) = corner(*index);
+                svg.push_str(&format!(
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"/>\n",
+                    x, y, cell, cell, color
+                ));
+            }
+        }
+
+        // an x-sudoku's diagonals go under the grid lines
+        if layout.has_diagonals() {
+            for (x1, x2) in [(margin, size - margin), (size - margin, margin)].iter() {
+                svg.push_str(&format!(
+                    "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"{:.1}\"/>\n",
+                    x1,
+                    margin,
+                    x2,
+                    size - margin,
+                    DIAGONAL_COLOR,
+                    cell / 30.0
+                ));
+            }
+        }
+
+        // thermometers, arrows and whispers go under the grid lines too
+        let at = |(x, y): (f64, f64)| (margin + x * cell, margin + y * cell);
+        for line in layout.lines() {
+            let mut points: Vec<(f64, f64)> = line
+                .cells
+                .iter()
+                .map(|index| at(cell_center(layout, *index)))
+                .collect();
+            let (color, width) = match line.kind {
+                LineKind::Thermometer => (THERMOMETER_COLOR, cell * 0.25),
+                LineKind::Arrow => (ARROW_COLOR, cell / 25.0),
+                LineKind::Whisper => (WHISPER_COLOR, cell * 0.12),
+            };
+
+            // a thermometer's bulb, or an arrow's circle and head
+            if line.kind == LineKind::Thermometer {
+                svg.push_str(&format!(
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"{}\"/>\n",
+                    points[0].0,
+                    points[0].1,
+                    cell * 0.35,
+                    color
+                ));
+            }
+            if line.kind == LineKind::Arrow {
+                svg.push_str(&format!(
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.1}\"/>\n",
+                    points[0].0,
+                    points[0].1,
+                    cell * 0.4,
+                    color,
+                    width
+                ));
+                let (shaft, head) = arrow_shape(layout, line, 0.4);
+                points = shaft.into_iter().map(at).collect();
+                let (x1, y1) = points[points.len() - 1];
+                for (x2, y2) in head.iter().map(|end| at(*end)) {
+                    svg.push_str(&format!(
+                        "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"{:.1}\" stroke-linecap=\"round\"/>\n",
+                        x1, y1, x2, y2, color, width
+                    ));
+                }
+            }
+            let points: Vec<String> = points
+                .iter()
+                .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+                .collect();
+            svg.push_str(&format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.1}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n",
+                points.join(" "),
+                color,
+                width
+            ));
+        }
+
+        // thin lines between cells, then thick ones along the edges of the
+        // medium boxes (or jigsaw regions)
+        let line = |(x1, y1): (f64, f64), (x2, y2): (f64, f64), width: f64| {
+            format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#000000\" stroke-width=\"{:.1}\" stroke-linecap=\"square\"/>\n",
+                x1, y1, x2, y2, width
+            )
+        };
+        for position in (0..=layout.size()).map(|line| margin + line as f64 * cell) {
+            svg.push_str(&line(
+                (position, margin),
+                (position, size - margin),
+                cell / 50.0,
+            ));
+            svg.push_str(&line(
+                (margin, position),
+                (size - margin, position),
+                cell / 50.0,
+            ));
+        }
+        for index in 0..layout.cell_count() {
+            let (x, y) = corner(index);
+            let [top, right, bottom, left] = layout.box_edges(index);
+            // every edge inside the board belongs to two cells, so each cell
+            // draws its right and bottom edges, and top and left ones only
+            // along the outside
+            let edges = [
+                (top && layout.row(index) == 0, (x, y), (x + cell, y)),
+                (right, (x + cell, y), (x + cell, y + cell)),
+                (bottom, (x, y + cell), (x + cell, y + cell)),
+                (left && layout.col(index) == 0, (x, y), (x, y + cell)),
+            ];
+            for (_, from, to) in edges.iter().filter(|(thick, _, _)| *thick) {
+                svg.push_str(&line(*from, *to, cell / 15.0));
+            }
+        }
+
+        // a killer's cages, dashed just inside the cell edges, with their sums
+        // in the top left corner
+        for index in 0..layout.cell_count() {
+            let (x, y) = corner(index);
+            for ((x1, y1), (x2, y2)) in cage_outline(layout, index, 0.08) {
+                svg.push_str(&format!(
+                    "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"{:.1}\" stroke-dasharray=\"{:.1} {:.1}\"/>\n",
+                    x + x1 * cell,
+                    y + y1 * cell,
+                    x + x2 * cell,
+                    y + y2 * cell,
+                    CAGE_COLOR,
+                    cell / 40.0,
+                    cell / 12.0,
+                    cell / 18.0
+                ));
+            }
+        }
+        for cage in layout.cages() {
+            if let Some(index) = cage_label_cell(cage) {
+                let (x, y) = corner(index);
+                svg.push_str(&format!(
+                    "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"sans-serif\" font-size=\"{:.1}\" fill=\"{}\">{}</text>\n",
+                    x + cell * 0.1,
+                    y + cell * 0.3,
+                    cell * 0.18,
+                    CAGE_COLOR,
+                    cage.sum
+                ));
+            }
+        }
+
+        // kropki dots, Xs and Vs go on the middle of the line between their
+        // cells
+        for (a, b, marker) in layout.markers() {
+            let ((x1, y1), (x2, y2)) = (corner(*a), corner(*b));
+            let (x, y) = ((x1 + x2 + cell) / 2.0, (y1 + y2 + cell) / 2.0);
+            let letter = match marker {
+                Marker::WhiteDot | Marker::BlackDot => {
+                    svg.push_str(&format!(
+                        "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"{}\" stroke=\"#000000\" stroke-width=\"{:.1}\"/>\n",
+                        x,
+                        y,
+                        cell * 0.12,
+                        if *marker == Marker::WhiteDot { "#ffffff" } else { "#000000" },
+                        cell / 40.0
+                    ));
+                    continue;
+                }
+                Marker::X => "X",
+                Marker::V => "V",
+            };
+            svg.push_str(&format!(
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#ffffff\"/>\n",
+                x - cell * 0.14,
+                y - cell * 0.14,
+                cell * 0.28,
+                cell * 0.28
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"sans-serif\" font-size=\"{:.1}\" font-weight=\"bold\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                x,
+                y,
+                cell * 0.3,
+                letter
+            ));
+        }
+
+        let text = |x: f64, y: f64, font_size: f64, color: &str, number: i32| {
+            format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"sans-serif\" font-size=\"{:.1}\" fill=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                x,
+                y,
+                font_size,
+                color,
+                Layout::symbol(number).unwrap_or('?')
+            )
+        };
+
+        for little_box in &self.little_boxes {
+            let index = little_box.index;
+            let (x, y) = corner(index);
+            let placement = placements.iter().find(|(cell, _)| *cell == index);
+
+            match (little_box.get_value(), placement) {
+                (Some(value), _) if layout.is_number(value) => {
+                    let color = if little_box.is_given() {
+                        GIVEN_COLOR
+                    } else {
+                        PLACED_COLOR
+                    };
+                    svg.push_str(&text(
+                        x + cell / 2.0,
+                        y + cell / 2.0,
+                        cell * 0.6,
+                        color,
+                        value,
+                    ));
+                }
+                (_, Some((_, value))) => {
+                    svg.push_str(&text(
+                        x + cell / 2.0,
+                        y + cell / 2.0,
+                        cell * 0.6,
+                        PLACED_COLOR,
+                        *value,
+                    ));
+                }
+                _ => {
+                    for note in little_box.get_possibles() {
+                        let eliminated = eliminations.contains(&(index, note));
+                        if !options.candidates && !eliminated {
+                            continue;
+                        }
+
+                        let (note_x, note_y) = note_center(index, note);
+                        if eliminated {
+                            svg.push_str(&format!(
+                                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{:.1}\"/>\n",
+                                note_x,
+                                note_y,
+                                note_size * 0.45,
+                                ELIMINATION_COLOR,
+                                cell / 40.0
+                            ));
+                        }
+                        let color = if eliminated {
+                            ELIMINATION_COLOR
+                        } else {
+                            CANDIDATE_COLOR
+                        };
+                        svg.push_str(&text(note_x, note_y, note_size * 0.75, color, note));
+                    }
+                }
+            }
+        }
+
+        // arrows go on top, stopping short of the notes so they can still be read
+        for ((from_index, from_note), (to_index, to_note)) in links {
+            if !on_board(from_index) || !on_board(to_index) {
+                continue;
+            }
+            let (x1, y1) = note_center(*from_index, *from_note);
+            let (x2, y2) = note_center(*to_index, *to_note);
+            let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+            if length == 0.0 {
+                continue;
+            }
+            let gap = note_size * 0.45 / length;
+            svg.push_str(&format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"{:.1}\" marker-end=\"url(#arrow)\"/>\n",
+                x1 + (x2 - x1) * gap,
+                y1 + (y2 - y1) * gap,
+                x2 - (x2 - x1) * gap,
+                y2 - (y2 - y1) * gap,
+                LINK_COLOR,
+                cell / 30.0
+            ));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::PUZZLE;
+    use crate::{Cage, Line};
+
+    #[test]
+    fn a_number_for_every_clue() {
+        let board: BigBox = PUZZLE.parse().unwrap();
+        let svg = board.to_svg(&SvgOptions::new());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"460.0\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(
+            svg.matches("<text").count(),
+            PUZZLE.matches(|c: char| c.is_ascii_digit()).count()
+        );
+    }
+
+    #[test]
+    fn steps_are_drawn() {
+        let mut board: BigBox = PUZZLE.parse().unwrap();
+        board.mark_impossible(board.scan_for_make_impossible());
+        let mut step = Step::new(&crate::XyWing);
+        step.placements.push((0, 4));
+        step.eliminations.push((1, 5));
+        step.links.push(((1, 5), (10, 5)));
+
+        let options = SvgOptions {
+            candidates: true,
+            highlighted: vec![80],
+            step: Some(step),
+            ..SvgOptions::new()
+        };
+        let svg = board.to_svg(&options);
+        assert_eq!(svg.matches(PLACEMENT_COLOR).count(), 1);
+        assert_eq!(svg.matches(HIGHLIGHT_COLOR).count(), 1);
+        assert!(svg.contains(&format!("stroke=\"{}\"", ELIMINATION_COLOR)));
+        assert!(svg.contains("marker-end=\"url(#arrow)\""));
+    }
+
+    #[test]
+    fn variants_are_drawn() {
+        let layout = Layout::standard()
+            .with_diagonals()
+            .with_cages(vec![Cage::new(17, vec![0, 1])])
+            .and_then(|layout| layout.with_markers(vec![(3, 4, Marker::BlackDot)]))
+            .and_then(|layout| {
+                layout.with_lines(vec![Line::new(LineKind::Thermometer, vec![20, 21, 22])])
+            })
+            .unwrap();
+        let svg = BigBox::with_layout(layout, vec![0; 81])
+            .unwrap()
+            .to_svg(&SvgOptions::new());
+        assert_eq!(svg.matches(DIAGONAL_COLOR).count(), 2);
+        assert!(svg.contains(">17</text>"));
+        assert!(svg.contains("stroke-dasharray"));
+        assert!(svg.contains("<polyline"));
+        assert!(svg.contains(&format!("fill=\"{}\"", THERMOMETER_COLOR)));
+    }
+}
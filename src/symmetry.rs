@@ -0,0 +1,205 @@
+// symmetric clue layouts. a symmetry splits the board into orbits, groups of
+// cells that map onto each other, and the clues of an orbit are all kept or all
+// taken away together. boards are 9x9 unless a size is given.
+
+use crate::Layout;
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    None,
+    // turning the board half way around
+    Rotational180,
+    // turning the board a quarter of the way around
+    Rotational90,
+    // mirrored over the top left to bottom right diagonal
+    Diagonal,
+    // mirrored top to bottom
+    Horizontal,
+    // mirrored left to right
+    Vertical,
+    // all the rotations and mirrors of a square at once
+    Dihedral,
+}
+
+// a way to move a cell around the board, given and returned as (row, col).
+// `last` is the last row and column of the board
+type Transform = fn((i32, i32), i32) -> (i32, i32);
+
+fn rotate_90((row, col): (i32, i32), last: i32) -> (i32, i32) {
+    (col, last - row)
+}
+
+fn rotate_180((row, col): (i32, i32), last: i32) -> (i32, i32) {
+    (last - row, last - col)
+}
+
+fn transpose((row, col): (i32, i32), _: i32) -> (i32, i32) {
+    (col, row)
+}
+
+fn flip_rows((row, col): (i32, i32), last: i32) -> (i32, i32) {
+    (last - row, col)
+}
+
+fn flip_cols((row, col): (i32, i32), last: i32) -> (i32, i32) {
+    (row, last - col)
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 7] = [
+        Symmetry::None,
+        Symmetry::Rotational180,
+        Symmetry::Rotational90,
+        Symmetry::Diagonal,
+        Symmetry::Horizontal,
+        Symmetry::Vertical,
+        Symmetry::Dihedral,
+    ];
+
+    // the moves that make up the symmetry, everything else comes from repeating them
+    fn transforms(&self) -> Vec<Transform> {
+        match self {
+            Symmetry::None => vec![],
+            Symmetry::Rotational180 => vec![rotate_180],
+            Symmetry::Rotational90 => vec![rotate_90],
+            Symmetry::Diagonal => vec![transpose],
+            Symmetry::Horizontal => vec![flip_rows],
+            Symmetry::Vertical => vec![flip_cols],
+            Symmetry::Dihedral => vec![rotate_90, transpose],
+        }
+    }
+
+    // every cell that an index maps onto, including itself, sorted
+    pub fn orbit(&self, index: i32) -> Vec<i32> {
+        self.orbit_in(index, 9)
+    }
+
+    // the same, on a board that's `size` cells across
+    pub fn orbit_in(&self, index: i32, size: i32) -> Vec<i32> {
+        let transforms = self.transforms();
+        let mut orbit: Vec<i32> = vec![index];
+        let mut i = 0;
+
+        while i < orbit.len() {
+            let cell = (orbit[i] / size, orbit[i] % size);
+            for transform in &transforms {
+                let (row, col) = transform(cell, size - 1);
+                let moved = row * size + col;
+                if !orbit.contains(&moved) {
+                    orbit.push(moved);
+                }
+            }
+            i += 1;
+        }
+
+        orbit.sort_unstable();
+        orbit
+    }
+
+    // the board split up into orbits, ordered by their first cell
+    pub fn orbits(&self) -> Vec<Vec<i32>> {
+        self.orbits_in(9)
+    }
+
+    pub fn orbits_in(&self, size: i32) -> Vec<Vec<i32>> {
+        let mut orbits: Vec<Vec<i32>> = Vec::new();
+        for index in 0..size * size {
+            if !orbits.iter().any(|orbit| orbit.contains(&index)) {
+                orbits.push(self.orbit_in(index, size));
+            }
+        }
+        orbits
+    }
+
+    // true if the filled in cells of a puzzle (0 is empty) follow the symmetry
+    pub fn is_followed_by(&self, values: &[i32]) -> bool {
+        let size = Layout::for_cell_count(values.len()).map_or(9, |layout| layout.size());
+        self.orbits_in(size).iter().all(|orbit| {
+            let filled = orbit
+                .iter()
+                .filter(|index| matches!(values.get(**index as usize), Some(value) if *value != 0))
+                .count();
+            filled == 0 || filled == orbit.len()
+        })
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Symmetry::None => "None",
+            Symmetry::Rotational180 => "Rotational 180",
+            Symmetry::Rotational90 => "Rotational 90",
+            Symmetry::Diagonal => "Diagonal",
+            Symmetry::Horizontal => "Horizontal",
+            Symmetry::Vertical => "Vertical",
+            Symmetry::Dihedral => "Dihedral",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// parses the names Display gives, in any case and with or without spaces
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Symmetry, String> {
+        let squash = |name: &str| name.replace(' ', "").to_lowercase();
+        Symmetry::ALL
+            .iter()
+            .find(|symmetry| squash(&symmetry.to_string()) == squash(s.trim()))
+            .copied()
+            .ok_or_else(|| format!("unknown symmetry: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orbits_cover_the_board_once() {
+        for symmetry in Symmetry::ALL.iter() {
+            let mut cells: Vec<i32> = symmetry.orbits().into_iter().flatten().collect();
+            cells.sort_unstable();
+            assert_eq!(cells, (0..81).collect::<Vec<i32>>());
+        }
+    }
+
+    #[test]
+    fn orbits_of_a_corner() {
+        assert_eq!(Symmetry::None.orbit(0), vec![0]);
+        assert_eq!(Symmetry::Rotational180.orbit(0), vec![0, 80]);
+        assert_eq!(Symmetry::Rotational90.orbit(0), vec![0, 8, 72, 80]);
+        assert_eq!(Symmetry::Diagonal.orbit(1), vec![1, 9]);
+        assert_eq!(Symmetry::Horizontal.orbit(0), vec![0, 72]);
+        assert_eq!(Symmetry::Vertical.orbit(0), vec![0, 8]);
+        assert_eq!(Symmetry::Dihedral.orbit(1).len(), 8);
+        assert_eq!(Symmetry::Rotational90.orbit_in(0, 4), vec![0, 3, 12, 15]);
+        assert_eq!(Symmetry::Dihedral.orbit(40), vec![40]);
+    }
+
+    #[test]
+    fn clue_layouts() {
+        let mut values = vec![0; 81];
+        values[0] = 1;
+        assert!(Symmetry::None.is_followed_by(&values));
+        assert!(!Symmetry::Rotational180.is_followed_by(&values));
+        values[80] = 2;
+        assert!(Symmetry::Rotational180.is_followed_by(&values));
+        assert!(!Symmetry::Rotational90.is_followed_by(&values));
+    }
+
+    #[test]
+    fn symmetry_names() {
+        for symmetry in Symmetry::ALL.iter() {
+            assert_eq!(symmetry.to_string().parse::<Symmetry>(), Ok(*symmetry));
+        }
+        assert_eq!(
+            "rotational180".parse::<Symmetry>(),
+            Ok(Symmetry::Rotational180)
+        );
+        assert!("sideways".parse::<Symmetry>().is_err());
+    }
+}
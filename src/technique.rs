@@ -0,0 +1,829 @@
+// the solving techniques, and the pipeline that runs them in order.
+//
+// every technique only looks at the board and reports one step it found,
+// the pipeline is the thing that applies the step and decides what to try next.
+// after any progress the pipeline goes back to the easiest technique, so the
+// harder ones are only ever used when nothing simpler works.
+
+use crate::{
+    Arrow, BigBox, CageCombinations, CageSplitting, GermanWhispers, InniesAndOuties, Kropki,
+    NonConsecutive, Thermometer, Xv,
+};
+
+// one deduction made by a technique
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Step {
+    pub technique: String,
+    pub difficulty: f32,
+    // format of tuples is index, value
+    pub placements: Vec<(i32, i32)>,
+    pub eliminations: Vec<(i32, i32)>,
+    // the chain the step follows, if it has one, as links from one (index, value)
+    // to the next
+    pub links: Vec<((i32, i32), (i32, i32))>,
+}
+
+impl Step {
+    pub fn new(technique: &dyn Technique) -> Step {
+        Step {
+            technique: technique.name().to_string(),
+            difficulty: technique.difficulty(),
+            placements: Vec::new(),
+            eliminations: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.placements.is_empty() && self.eliminations.is_empty()
+    }
+}
+
+// anything that can look at a board and find a step. implement this in other
+// crates to plug your own techniques into a `Pipeline`.
+pub trait Technique {
+    fn name(&self) -> &str;
+
+    // roughly on the Sudoku Explainer scale, used to order the pipeline
+    fn difficulty(&self) -> f32;
+
+    // find one step, it has to change something on the board or be None
+    fn find(&self, board: &BigBox) -> Option<Step>;
+}
+
+// an ordered list of techniques that can be turned on and off
+pub struct Pipeline {
+    techniques: Vec<(Box<dyn Technique>, bool)>,
+}
+
+impl Default for Pipeline {
+    fn default() -> Pipeline {
+        Pipeline::standard()
+    }
+}
+
+impl Pipeline {
+    // a pipeline with nothing in it, push techniques into it yourself
+    pub fn new() -> Pipeline {
+        Pipeline {
+            techniques: Vec::new(),
+        }
+    }
+
+    // all the techniques that come with this crate, easiest first, so the hints
+    // and the ratings use the same order. the variant ones don't find anything
+    // on a board without their rules
+    pub fn standard() -> Pipeline {
+        let mut pipeline = Pipeline::new();
+        pipeline.push(Box::new(HiddenSingle));
+        pipeline.push(Box::new(NonConsecutive));
+        pipeline.push(Box::new(Kropki));
+        pipeline.push(Box::new(Xv));
+        pipeline.push(Box::new(Thermometer));
+        pipeline.push(Box::new(GermanWhispers));
+        pipeline.push(Box::new(CageCombinations));
+        pipeline.push(Box::new(Arrow));
+        pipeline.push(Box::new(NakedSingle));
+        pipeline.push(Box::new(InniesAndOuties));
+        pipeline.push(Box::new(Pointing));
+        pipeline.push(Box::new(Claiming));
+        pipeline.push(Box::new(CageSplitting));
+        pipeline.push(Box::new(NakedSubset::new(2)));
+        pipeline.push(Box::new(BasicFish::new(2)));
+        pipeline.push(Box::new(HiddenSubset::new(2)));
+        pipeline.push(Box::new(NakedSubset::new(3)));
+        pipeline.push(Box::new(BasicFish::new(3)));
+        pipeline.push(Box::new(HiddenSubset::new(3)));
+        pipeline.push(Box::new(XyWing));
+        pipeline.sort_by_difficulty();
+        pipeline
+    }
+
+    // add a technique at the end of the pipeline
+    pub fn push(&mut self, technique: Box<dyn Technique>) {
+        self.techniques.push((technique, true));
+    }
+
+    // reorder the techniques from easiest to hardest
+    pub fn sort_by_difficulty(&mut self) {
+        self.techniques.sort_by(|(a, _), (b, _)| {
+            a.difficulty()
+                .partial_cmp(&b.difficulty())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+
+    // returns false if there's no technique with that name
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        let mut found = false;
+        for (technique, is_enabled) in self.techniques.iter_mut() {
+            if technique.name() == name {
+                *is_enabled = enabled;
+                found = true;
+            }
+        }
+        found
+    }
+
+    // turn off every technique harder than max_difficulty, and turn on the rest
+    pub fn limit_difficulty(&mut self, max_difficulty: f32) {
+        for (technique, is_enabled) in self.techniques.iter_mut() {
+            *is_enabled = technique.difficulty() <= max_difficulty;
+        }
+    }
+
+    // names of the techniques that are turned on, in the order they're tried
+    pub fn enabled_names(&self) -> Vec<String> {
+        self.techniques
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(technique, _)| technique.name().to_string())
+            .collect()
+    }
+
+    // find the next step using the easiest technique that works
+    pub fn next_step(&self, board: &BigBox) -> Option<Step> {
+        self.techniques
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .find_map(|(technique, _)| technique.find(board))
+    }
+
+    // keep applying steps until the puzzle is solved or nothing works anymore.
+    // candidates have to be filled in already (see BigBox::solve_puzzle)
+    pub fn run(&self, board: &mut BigBox) -> Vec<Step> {
+        let mut steps: Vec<Step> = Vec::new();
+
+        while !board.is_solved() {
+            match self.next_step(board) {
+                Some(step) if board.apply_step(&step) => steps.push(step),
+                _ => break,
+            }
+        }
+
+        steps
+    }
+}
+
+// get every combination of `size` items out of the list
+pub fn combinations(items: &[i32], size: usize) -> Vec<Vec<i32>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    if items.len() < size {
+        return Vec::new();
+    }
+
+    let mut return_vec: Vec<Vec<i32>> = Vec::new();
+    for (i, first) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], size - 1) {
+            rest.insert(0, *first);
+            return_vec.push(rest);
+        }
+    }
+    return_vec
+}
+
+// the empty cells in a group that can still be `note`
+fn cells_with_note(board: &BigBox, group: &[i32], note: i32) -> Vec<i32> {
+    group
+        .iter()
+        .filter(|cell| {
+            board.little_boxes[**cell as usize]
+                .get_possibles()
+                .contains(&note)
+        })
+        .copied()
+        .collect()
+}
+
+// split a group up by `key` (the row or column of each cell), smallest key
+// first. the cells stay in the order they were in
+fn split_by(group: &[i32], key: impl Fn(i32) -> i32) -> Vec<Vec<i32>> {
+    let mut keys: Vec<i32> = group.iter().map(|cell| key(*cell)).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    keys.iter()
+        .map(|k| {
+            group
+                .iter()
+                .filter(|cell| key(**cell) == *k)
+                .copied()
+                .collect()
+        })
+        .collect()
+}
+
+// a cell that only has one possibility left
+pub struct NakedSingle;
+
+impl Technique for NakedSingle {
+    fn name(&self) -> &str {
+        "Naked Single"
+    }
+
+    fn difficulty(&self) -> f32 {
+        2.3
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        let (index, value) = *board.scan_one_possible().first()?;
+        let mut step = Step::new(self);
+        step.placements.push((index, value));
+        Some(step)
+    }
+}
+
+// a note that only one cell in a row/col/med box can have
+pub struct HiddenSingle;
+
+impl Technique for HiddenSingle {
+    fn name(&self) -> &str {
+        "Hidden Single"
+    }
+
+    fn difficulty(&self) -> f32 {
+        1.5
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        for group in board.get_all_groups_indexes() {
+            for note in board.layout().numbers() {
+                let cells = cells_with_note(board, &group, note);
+                if cells.len() == 1 {
+                    let mut step = Step::new(self);
+                    step.placements.push((cells[0], note));
+                    return Some(step);
+                }
+            }
+        }
+        None
+    }
+}
+
+// when a note in a medium box is only possible in one of its rows or columns,
+// that note can't be anywhere else in the same row or column
+pub struct Pointing;
+
+impl Technique for Pointing {
+    fn name(&self) -> &str {
+        "Pointing"
+    }
+
+    fn difficulty(&self) -> f32 {
+        2.6
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        let layout = board.layout();
+
+        for med_box in layout.boxes() {
+            // the parts of the rows and columns inside the medium box, so on a
+            // normal board rows are [0, 1, 2], [3, 4, 5] and [6, 7, 8] in the
+            // medium box, columns are [0, 3, 6], [1, 4, 7] and [2, 5, 8]. jigsaw
+            // regions can have any number of cells in a row
+            let rows = split_by(&med_box, |cell| layout.row(cell));
+            let cols = split_by(&med_box, |cell| layout.col(cell));
+
+            for (lines, get_line) in [
+                (
+                    rows,
+                    BigBox::get_row_indices as fn(&BigBox, &i32) -> Vec<i32>,
+                ),
+                (
+                    cols,
+                    BigBox::get_col_indices as fn(&BigBox, &i32) -> Vec<i32>,
+                ),
+            ]
+            .iter()
+            {
+                let possibles: Vec<Vec<i32>> = lines
+                    .iter()
+                    .map(|line| board.get_group_possibles(line))
+                    .collect();
+
+                for (i, line) in lines.iter().enumerate() {
+                    let others: Vec<i32> = possibles
+                        .iter()
+                        .enumerate()
+                        .filter(|(other, _)| *other != i)
+                        .flat_map(|(_, notes)| notes.iter().copied())
+                        .collect();
+                    let unique =
+                        BigBox::main_vs_sub_unique_possibilities(&possibles[i], &others, &[]);
+
+                    let mut step = Step::new(self);
+                    for cell in get_line(board, &line[0]) {
+                        if line.contains(&cell) {
+                            continue;
+                        }
+                        for note in &unique {
+                            if board.little_boxes[cell as usize]
+                                .get_possibles()
+                                .contains(note)
+                            {
+                                step.eliminations.push((cell, *note));
+                            }
+                        }
+                    }
+
+                    if !step.is_empty() {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+// `size` cells in a group that only have `size` notes between them, so those
+// notes can be removed from everything else in the group (pairs, triples, ...)
+pub struct NakedSubset {
+    size: usize,
+}
+
+impl NakedSubset {
+    pub fn new(size: usize) -> NakedSubset {
+        NakedSubset { size }
+    }
+}
+
+impl Technique for NakedSubset {
+    fn name(&self) -> &str {
+        match self.size {
+            2 => "Naked Pair",
+            3 => "Naked Triple",
+            4 => "Naked Quad",
+            _ => "Naked Subset",
+        }
+    }
+
+    fn difficulty(&self) -> f32 {
+        match self.size {
+            2 => 3.0,
+            3 => 3.6,
+            _ => 5.0,
+        }
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        for group in board.get_all_groups_indexes() {
+            let empty_cells: Vec<i32> = group
+                .iter()
+                .filter(|cell| board.get_little_box_value(cell).is_none())
+                .copied()
+                .collect();
+
+            for cells in combinations(&empty_cells, self.size) {
+                let notes = board.get_group_possibles(&cells);
+                if notes.len() != self.size {
+                    continue;
+                }
+
+                let mut step = Step::new(self);
+                for cell in &empty_cells {
+                    if cells.contains(cell) {
+                        continue;
+                    }
+                    for note in board.little_boxes[*cell as usize].get_possibles() {
+                        if notes.contains(&note) {
+                            step.eliminations.push((*cell, note));
+                        }
+                    }
+                }
+
+                if !step.is_empty() {
+                    return Some(step);
+                }
+            }
+        }
+        None
+    }
+}
+
+// `size` notes in a group that only fit in the same `size` cells, so those
+// cells can't be anything else
+pub struct HiddenSubset {
+    size: usize,
+}
+
+impl HiddenSubset {
+    pub fn new(size: usize) -> HiddenSubset {
+        HiddenSubset { size }
+    }
+}
+
+impl Technique for HiddenSubset {
+    fn name(&self) -> &str {
+        match self.size {
+            2 => "Hidden Pair",
+            3 => "Hidden Triple",
+            4 => "Hidden Quad",
+            _ => "Hidden Subset",
+        }
+    }
+
+    fn difficulty(&self) -> f32 {
+        match self.size {
+            2 => 3.4,
+            3 => 4.0,
+            _ => 5.4,
+        }
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        for group in board.get_all_groups_indexes() {
+            // only notes that are still open somewhere in the group count
+            let open_notes: Vec<i32> = board.get_group_possibles(&group);
+
+            for notes in combinations(&open_notes, self.size) {
+                let mut cells: Vec<i32> = Vec::new();
+                for note in &notes {
+                    for cell in cells_with_note(board, &group, *note) {
+                        if !cells.contains(&cell) {
+                            cells.push(cell);
+                        }
+                    }
+                }
+                if cells.len() != self.size {
+                    continue;
+                }
+
+                let mut step = Step::new(self);
+                for cell in &cells {
+                    for note in board.little_boxes[*cell as usize].get_possibles() {
+                        if !notes.contains(&note) {
+                            step.eliminations.push((*cell, note));
+                        }
+                    }
+                }
+
+                if !step.is_empty() {
+                    return Some(step);
+                }
+            }
+        }
+        None
+    }
+}
+
+// when a note in a row or column is only possible inside one medium box,
+// that note can't be anywhere else in the medium box
+pub struct Claiming;
+
+impl Technique for Claiming {
+    fn name(&self) -> &str {
+        "Claiming"
+    }
+
+    fn difficulty(&self) -> f32 {
+        2.8
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        let lines = board
+            .layout()
+            .rows()
+            .into_iter()
+            .chain(board.layout().cols());
+
+        for line in lines {
+            for note in board.layout().numbers() {
+                let cells = cells_with_note(board, &line, note);
+                if cells.len() < 2 {
+                    continue;
+                }
+
+                let med_box = board.get_box_indices(&cells[0]);
+                if !cells.iter().all(|cell| med_box.contains(cell)) {
+                    continue;
+                }
+
+                let mut step = Step::new(self);
+                for cell in cells_with_note(board, &med_box, note) {
+                    if !line.contains(&cell) {
+                        step.eliminations.push((cell, note));
+                    }
+                }
+
+                if !step.is_empty() {
+                    return Some(step);
+                }
+            }
+        }
+        None
+    }
+}
+
+// `size` rows where a note only fits in the same `size` columns (or the other
+// way around), so the note can be removed from the rest of those columns.
+// 2 is an X-Wing, 3 is a Swordfish and 4 is a Jellyfish
+pub struct BasicFish {
+    size: usize,
+}
+
+impl BasicFish {
+    pub fn new(size: usize) -> BasicFish {
+        BasicFish { size }
+    }
+
+    fn find_in(
+        &self,
+        board: &BigBox,
+        note: i32,
+        base_lines: &[Vec<i32>],
+        cover_line: fn(&BigBox, &i32) -> Vec<i32>,
+    ) -> Option<Step> {
+        // the lines that have the note in 2 to `size` places, listed by position in base_lines
+        let candidate_lines: Vec<i32> = (0..base_lines.len() as i32)
+            .filter(|line| {
+                let count = cells_with_note(board, &base_lines[*line as usize], note).len();
+                count >= 2 && count <= self.size
+            })
+            .collect();
+
+        for lines in combinations(&candidate_lines, self.size) {
+            let mut base_cells: Vec<i32> = Vec::new();
+            for line in &lines {
+                base_cells.append(&mut cells_with_note(
+                    board,
+                    &base_lines[*line as usize],
+                    note,
+                ));
+            }
+
+            let mut cover_cells: Vec<i32> = Vec::new();
+            let mut covers = 0;
+            for cell in &base_cells {
+                if !cover_cells.contains(cell) {
+                    covers += 1;
+                    for cover_cell in cover_line(board, cell) {
+                        if !cover_cells.contains(&cover_cell) {
+                            cover_cells.push(cover_cell);
+                        }
+                    }
+                }
+            }
+            if covers != self.size {
+                continue;
+            }
+
+            let mut step = Step::new(self);
+            for cell in cells_with_note(board, &cover_cells, note) {
+                if !base_cells.contains(&cell) {
+                    step.eliminations.push((cell, note));
+                }
+            }
+
+            if !step.is_empty() {
+                return Some(step);
+            }
+        }
+        None
+    }
+}
+
+impl Technique for BasicFish {
+    fn name(&self) -> &str {
+        match self.size {
+            2 => "X-Wing",
+            3 => "Swordfish",
+            4 => "Jellyfish",
+            _ => "Fish",
+        }
+    }
+
+    fn difficulty(&self) -> f32 {
+        match self.size {
+            2 => 3.2,
+            3 => 3.8,
+            _ => 5.2,
+        }
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        let rows = board.layout().rows();
+        let cols = board.layout().cols();
+
+        for note in board.layout().numbers() {
+            if let Some(step) = self.find_in(board, note, &rows, BigBox::get_col_indices) {
+                return Some(step);
+            }
+            if let Some(step) = self.find_in(board, note, &cols, BigBox::get_row_indices) {
+                return Some(step);
+            }
+        }
+        None
+    }
+}
+
+// a cell with notes xy that sees one cell with xz and another with yz. whichever
+// way the xy cell goes, one of the other two is z, so anything that sees both of
+// them can't be z
+pub struct XyWing;
+
+impl Technique for XyWing {
+    fn name(&self) -> &str {
+        "XY-Wing"
+    }
+
+    fn difficulty(&self) -> f32 {
+        4.2
+    }
+
+    fn find(&self, board: &BigBox) -> Option<Step> {
+        let bivalue = |index: i32| {
+            let possibles = board.little_boxes[index as usize].get_possibles();
+            if possibles.len() == 2 {
+                Some(possibles)
+            } else {
+                None
+            }
+        };
+
+        for pivot in 0..board.layout().cell_count() {
+            let pivot_notes = match bivalue(pivot) {
+                Some(notes) => notes,
+                None => continue,
+            };
+            let (x, y) = (pivot_notes[0], pivot_notes[1]);
+
+            let wings: Vec<(i32, Vec<i32>)> = board
+                .get_all_affected_indices(pivot)
+                .into_iter()
+                .filter(|cell| *cell != pivot)
+                .filter_map(|cell| bivalue(cell).map(|notes| (cell, notes)))
+                .collect();
+
+            for (wing_1, notes_1) in &wings {
+                // the first wing has x and some z
+                if !notes_1.contains(&x) || notes_1.contains(&y) {
+                    continue;
+                }
+                let z = if notes_1[0] == x {
+                    notes_1[1]
+                } else {
+                    notes_1[0]
+                };
+
+                for (wing_2, notes_2) in &wings {
+                    if !notes_2.contains(&y) || !notes_2.contains(&z) {
+                        continue;
+                    }
+
+                    let peers_2 = board.get_all_affected_indices(*wing_2);
+                    let mut step = Step::new(self);
+                    for cell in board.get_all_affected_indices(*wing_1) {
+                        if cell != *wing_1
+                            && cell != *wing_2
+                            && cell != pivot
+                            && peers_2.contains(&cell)
+                            && board.little_boxes[cell as usize]
+                                .get_possibles()
+                                .contains(&z)
+                        {
+                            step.eliminations.push((cell, z));
+                        }
+                    }
+
+                    if !step.is_empty() {
+                        // if the first wing isn't z it's x, so the pivot is y,
+                        // so the second wing is z
+                        step.links.push(((*wing_1, x), (pivot, x)));
+                        step.links.push(((pivot, y), (*wing_2, y)));
+                        return Some(step);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{board_with_notes, eliminations, PUZZLE};
+
+    fn take_out(board: &mut BigBox, cells: impl IntoIterator<Item = i32>, note: i32) {
+        for cell in cells {
+            board.little_boxes[cell as usize].make_impossible(note);
+        }
+    }
+
+    #[test]
+    fn singles() {
+        let board = board_with_notes(&[(40, &[5])]);
+        assert_eq!(NakedSingle.find(&board).unwrap().placements, vec![(40, 5)]);
+
+        let mut board = board_with_notes(&[]);
+        take_out(&mut board, (0..9).filter(|cell| *cell != 4), 7);
+        assert_eq!(HiddenSingle.find(&board).unwrap().placements, vec![(4, 7)]);
+        assert!(NakedSingle.find(&board).is_none());
+    }
+
+    #[test]
+    fn pointing_and_claiming() {
+        // 5 in the top left box is only in the top row
+        let mut board = board_with_notes(&[]);
+        take_out(&mut board, vec![9, 10, 11, 18, 19, 20], 5);
+        assert_eq!(
+            eliminations(&Pointing, &board),
+            (3..9).map(|cell| (cell, 5)).collect::<Vec<_>>()
+        );
+
+        // 5 in the top row is only in the top left box
+        let mut board = board_with_notes(&[]);
+        take_out(&mut board, 2..9, 5);
+        assert_eq!(
+            eliminations(&Claiming, &board),
+            vec![(9, 5), (10, 5), (11, 5), (18, 5), (19, 5), (20, 5)]
+        );
+    }
+
+    #[test]
+    fn subsets() {
+        let board = board_with_notes(&[(0, &[1, 2]), (1, &[1, 2])]);
+        let expected: Vec<(i32, i32)> =
+            (2..9).flat_map(|cell| vec![(cell, 1), (cell, 2)]).collect();
+        assert_eq!(eliminations(&NakedSubset::new(2), &board), expected);
+
+        let mut board = board_with_notes(&[]);
+        take_out(&mut board, 2..9, 1);
+        take_out(&mut board, 2..9, 2);
+        let expected: Vec<(i32, i32)> = (0..2)
+            .flat_map(|cell| (3..=9).map(move |note| (cell, note)))
+            .collect();
+        assert_eq!(eliminations(&HiddenSubset::new(2), &board), expected);
+    }
+
+    #[test]
+    fn x_wing() {
+        // 5 in the first and fifth rows is only in the first and fifth columns
+        let mut board = board_with_notes(&[]);
+        let rows = (0..9).chain(36..45);
+        take_out(
+            &mut board,
+            rows.filter(|cell| cell % 9 != 0 && cell % 9 != 4),
+            5,
+        );
+
+        let expected: Vec<(i32, i32)> = (0..81)
+            .filter(|cell| (cell % 9 == 0 || cell % 9 == 4) && cell / 9 != 0 && cell / 9 != 4)
+            .map(|cell| (cell, 5))
+            .collect();
+        assert_eq!(eliminations(&BasicFish::new(2), &board), expected);
+    }
+
+    #[test]
+    fn xy_wing() {
+        // the pivot is 1 or 2, the wings are 1 or 3 and 2 or 3
+        let board = board_with_notes(&[(0, &[1, 2]), (3, &[1, 3]), (27, &[2, 3])]);
+        let step = XyWing.find(&board).unwrap();
+        assert_eq!(step.eliminations, vec![(30, 3)]);
+        assert_eq!(step.links.len(), 2);
+    }
+
+    #[test]
+    fn pipeline_turns_techniques_on_and_off() {
+        let mut pipeline = Pipeline::standard();
+        assert!(pipeline.set_enabled("X-Wing", false));
+        assert!(!pipeline.set_enabled("Not A Technique", false));
+        assert!(!pipeline.enabled_names().contains(&"X-Wing".to_string()));
+
+        pipeline.limit_difficulty(1.5);
+        assert_eq!(pipeline.enabled_names(), vec!["Hidden Single"]);
+
+        // the easy puzzle only needs singles
+        let mut board: BigBox = PUZZLE.parse().unwrap();
+        let steps = board.solve_puzzle_with(&pipeline);
+        assert!(board.is_solved());
+        assert!(steps.iter().all(|step| step.technique == "Hidden Single"));
+    }
+
+    #[test]
+    fn standard_pipeline_is_easiest_first() {
+        let pipeline = Pipeline::standard();
+        let difficulties: Vec<f32> = pipeline
+            .techniques
+            .iter()
+            .map(|(technique, _)| technique.difficulty())
+            .collect();
+        assert!(difficulties.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(
+            pipeline.enabled_names()[..2],
+            ["Hidden Single", "Non-Consecutive"]
+        );
+    }
+
+    #[test]
+    fn combinations_in_order() {
+        assert_eq!(
+            combinations(&[1, 2, 3], 2),
+            vec![vec![1, 2], vec![1, 3], vec![2, 3]]
+        );
+        assert!(combinations(&[1], 2).is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+// things the tests in every module share: a puzzle to try things on and the
+// usual ways of setting up a board and seeing what a technique does with it
+
+use crate::{BigBox, Layout, Technique};
+
+// a puzzle with one solution that singles are enough for
+pub(crate) const PUZZLE: &str =
+    "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..";
+
+// an empty board with the layout, and the values put in
+pub(crate) fn board_with(layout: Layout, values: &[(i32, i32)]) -> BigBox {
+    let cells = layout.cell_count() as usize;
+    let mut board = BigBox::with_layout(layout, vec![0; cells]).unwrap();
+    for (index, value) in values {
+        board.update_little_box_value(index, value);
+    }
+    board
+}
+
+// an empty 9x9 board where the cells listed only have the notes given
+pub(crate) fn board_with_notes(notes: &[(i32, &[i32])]) -> BigBox {
+    let mut board = BigBox::new(vec![0; 81]).unwrap();
+    for (index, cell_notes) in notes {
+        let little_box = &mut board.little_boxes[*index as usize];
+        little_box
+            .make_many_impossible((1..=9).filter(|note| !cell_notes.contains(note)).collect());
+    }
+    board
+}
+
+// what the technique's first step takes out, sorted
+pub(crate) fn eliminations(technique: &dyn Technique, board: &BigBox) -> Vec<(i32, i32)> {
+    let mut eliminations = technique
+        .find(board)
+        .map(|step| step.eliminations)
+        .unwrap_or_default();
+    eliminations.sort_unstable();
+    eliminations
+}
@@ -0,0 +1,418 @@
+// reading and writing puzzles as text. the usual format is 81 characters on
+// one line, but puzzles copied off websites often come as a grid with lines
+// drawn in, so those are read too:
+//
+//     4 . . | . . . | 8 . 5
+//     . 3 . | . . . | . . .
+//     ------+-------+------
+//     ...
+//
+// numbers 1 to 9 are clues, `0`, `.`, `*` and `_` are empty cells, and spaces,
+// line breaks, `|`, `-` and `+` are skipped. bigger boards use the letters A to
+// P for 10 to 25, and how big the board is comes from how many cells there are
+// (16 for 4x4, 36 for 6x6, 81 for the usual 9x9 and so on).
+//
+// there are also grids drawn for people to read, with or without the notes,
+// and the grid with notes can be read back in.
+
+use crate::{BigBox, Layout, LittleBox};
+use std::{fmt, str::FromStr};
+
+impl FromStr for BigBox {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<BigBox, String> {
+        let mut values: Vec<i32> = Vec::new();
+
+        for c in s.chars() {
+            match c {
+                '0' | '.' | '*' | '_' => values.push(0),
+                '|' | '-' | '+' => (),
+                c if c.is_whitespace() => (),
+                c => match Layout::value_of(c) {
+                    Some(value) => values.push(value),
+                    None => return Err(format!("unexpected character '{}' in puzzle", c)),
+                },
+            }
+        }
+
+        // check the length first, BigBox::new logs to the browser console
+        let layout = Layout::for_cell_count(values.len()).ok_or_else(|| {
+            format!(
+                "puzzle has {} cells, it should have 81 (or 16, 36, ...)",
+                values.len()
+            )
+        })?;
+        if let Some(value) = values.iter().find(|value| **value > layout.size()) {
+            return Err(format!(
+                "{} is too big for a {}x{} puzzle",
+                value,
+                layout.size(),
+                layout.size()
+            ));
+        }
+        BigBox::with_layout(layout, values)
+            .ok_or_else(|| "couldn't make a board out of the puzzle".to_string())
+    }
+}
+
+// for the places that only handle the usual 9x9 board, like the file formats
+pub(crate) fn parse_nine_by_nine(s: &str) -> Result<BigBox, String> {
+    let board = s.parse::<BigBox>()?;
+    nine_by_nine(board)
+}
+
+pub(crate) fn nine_by_nine(board: BigBox) -> Result<BigBox, String> {
+    check_nine_by_nine(&board)?;
+    Ok(board)
+}
+
+// the same check for a board that's only being read, like one being written out
+pub(crate) fn check_nine_by_nine(board: &BigBox) -> Result<(), String> {
+    if board.layout().size() != 9 {
+        let size = board.layout().size();
+        return Err(format!(
+            "only 9x9 puzzles can be used here, this one is {}x{}",
+            size, size
+        ));
+    }
+    Ok(())
+}
+
+// the 81 character line, with `.` for empty cells
+impl fmt::Display for BigBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for little_box in &self.little_boxes {
+            match little_box.get_value() {
+                Some(i) if self.layout.is_number(i) => {
+                    write!(f, "{}", Layout::symbol(i).unwrap_or('.'))?
+                }
+                _ => write!(f, ".")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// which characters the grids are drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridStyle {
+    // plain characters, safe to paste anywhere
+    Ascii,
+    // box drawing characters, looks nicer in a terminal
+    Unicode,
+}
+
+// the characters for one horizontal line of the grid: left end, where it
+// crosses a thick line, right end, and the line itself
+struct Border {
+    left: char,
+    cross: char,
+    right: char,
+    line: char,
+}
+
+struct Borders {
+    top: Border,
+    middle: Border,
+    bottom: Border,
+    side: char,
+}
+
+impl GridStyle {
+    // the plain grid uses + for corners so it can be pasted back in, the
+    // pencilmark grid uses the . : ' corners that solving forums use
+    fn borders(&self, pencilmarks: bool) -> Borders {
+        let border = |left, cross, right, line| Border {
+            left,
+            cross,
+            right,
+            line,
+        };
+
+        match (self, pencilmarks) {
+            (GridStyle::Ascii, false) => Borders {
+                top: border('+', '+', '+', '-'),
+                middle: border('+', '+', '+', '-'),
+                bottom: border('+', '+', '+', '-'),
+                side: '|',
+            },
+            (GridStyle::Ascii, true) => Borders {
+                top: border('.', '.', '.', '-'),
+                middle: border(':', '+', ':', '-'),
+                bottom: border('\'', '\'', '\'', '-'),
+                side: '|',
+            },
+            (GridStyle::Unicode, _) => Borders {
+                top: border('┌', '┬', '┐', '─'),
+                middle: border('├', '┼', '┤', '─'),
+                bottom: border('└', '┴', '┘', '─'),
+                side: '│',
+            },
+        }
+    }
+}
+
+// draw the cells as a grid with lines around the medium boxes. every column is
+// as wide as the widest cell in it. jigsaw regions can't be drawn with straight
+// lines right across, so a jigsaw just gets the line around the outside
+fn draw_grid(cells: &[String], layout: &Layout, borders: &Borders) -> String {
+    let size = layout.size() as usize;
+    let (box_width, box_height) = if layout.is_jigsaw() {
+        (size, size)
+    } else {
+        (layout.box_width() as usize, layout.box_height() as usize)
+    };
+    let widths: Vec<usize> = (0..size)
+        .map(|col| {
+            (0..size)
+                .map(|row| cells[row * size + col].chars().count())
+                .max()
+                .unwrap_or(1)
+        })
+        .collect();
+
+    let border_line = |border: &Border| {
+        let stacks: Vec<String> = (0..size / box_width)
+            .map(|stack| {
+                // the cells, a space between each of them and one on either side
+                let first = stack * box_width;
+                let width: usize =
+                    widths[first..first + box_width].iter().sum::<usize>() + box_width + 1;
+                border.line.to_string().repeat(width)
+            })
+            .collect();
+        format!(
+            "{}{}{}\n",
+            border.left,
+            stacks.join(&border.cross.to_string()),
+            border.right
+        )
+    };
+
+    let mut grid = border_line(&borders.top);
+    for row in 0..size {
+        if row != 0 && row % box_height == 0 {
+            grid.push_str(&border_line(&borders.middle));
+        }
+
+        let mut line = String::new();
+        for col in 0..size {
+            if col % box_width == 0 {
+                line.push(borders.side);
+                line.push(' ');
+            }
+            line.push_str(&format!(
+                "{:<width$} ",
+                cells[row * size + col],
+                width = widths[col]
+            ));
+        }
+        line.push(borders.side);
+        grid.push_str(&line);
+        grid.push('\n');
+    }
+    grid.push_str(&border_line(&borders.bottom));
+
+    grid
+}
+
+impl BigBox {
+    // the board with lines around the medium boxes and `.` for empty cells
+    pub fn to_grid(&self, style: GridStyle) -> String {
+        let cells: Vec<String> = self
+            .little_boxes
+            .iter()
+            .map(|little_box| little_box.get_pretty_value().replace(' ', "."))
+            .collect();
+        draw_grid(&cells, &self.layout, &style.borders(false))
+    }
+
+    // every empty cell shows all of its notes, the way puzzles get posted on
+    // solving forums. filled in cells just show their value
+    pub fn to_pencilmark_grid(&self, style: GridStyle) -> String {
+        let cells: Vec<String> = self
+            .little_boxes
+            .iter()
+            .map(|little_box| match little_box.get_value() {
+                Some(i) if self.layout.is_number(i) => little_box.get_pretty_value(),
+                _ => {
+                    let notes: String = little_box
+                        .get_possibles()
+                        .iter()
+                        .filter_map(|note| Layout::symbol(*note))
+                        .collect();
+                    if notes.is_empty() {
+                        ".".to_string()
+                    } else {
+                        notes
+                    }
+                }
+            })
+            .collect();
+        draw_grid(&cells, &self.layout, &style.borders(true))
+    }
+
+    // read a pencilmark grid like the ones to_pencilmark_grid draws (and HoDoKu
+    // and the solving forums post). a cell with one number is a filled in cell,
+    // a cell with more is the notes that are left, and a `.` on its own is a
+    // cell without any notes. the notes are set exactly as written instead of
+    // being worked out again, so eliminations somebody else already made are
+    // kept. the grid doesn't say which numbers were the clues, so the filled in
+    // cells are read as placed rather than given, and can still be changed
+    pub fn from_pencilmark_grid(text: &str) -> Result<BigBox, String> {
+        // lines with - or ─ in them and no numbers are the borders (their
+        // corners can be dots too). everything else that isn't a number or a
+        // dot is a separator
+        let cells: Vec<String> = text
+            .lines()
+            .filter(|line| {
+                line.chars().any(|c| c.is_ascii_alphanumeric())
+                    || !line.chars().any(|c| c == '-' || c == '─')
+            })
+            .flat_map(|line| {
+                line.chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() || c == '.' {
+                            c
+                        } else {
+                            ' '
+                        }
+                    })
+                    .collect::<String>()
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect::<Vec<String>>()
+            })
+            .collect();
+
+        let layout = Layout::for_cell_count(cells.len()).ok_or_else(|| {
+            format!(
+                "pencilmark grid has {} cells, it should have 81 (or 16, 36, ...)",
+                cells.len()
+            )
+        })?;
+
+        let mut little_boxes: Vec<LittleBox> = Vec::new();
+        for (index, cell) in cells.iter().enumerate() {
+            let numbers: Vec<i32> = if cell == "." {
+                Vec::new()
+            } else {
+                cell.chars()
+                    .map(|c| Layout::value_of(c).filter(|number| layout.is_number(*number)))
+                    .collect::<Option<Vec<i32>>>()
+                    .ok_or_else(|| {
+                        format!(
+                            "cell {} has something that isn't a number in it: {}",
+                            index, cell
+                        )
+                    })?
+            };
+
+            let mut little_box = LittleBox::with_size(0, index as i32, layout.size()).unwrap();
+            if numbers.len() == 1 {
+                little_box.update_value(numbers[0]);
+            } else {
+                for number in layout.numbers() {
+                    if !numbers.contains(&number) {
+                        little_box.make_impossible(number);
+                    }
+                }
+            }
+            little_boxes.push(little_box);
+        }
+
+        Ok(BigBox {
+            little_boxes,
+            times_updated: 0,
+            layout,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::PUZZLE;
+
+    #[test]
+    fn line_round_trip() {
+        let board: BigBox = PUZZLE.parse().unwrap();
+        assert_eq!(board.to_string(), PUZZLE);
+    }
+
+    #[test]
+    fn odd_lengths_are_errors() {
+        assert!("1".repeat(49).parse::<BigBox>().is_err());
+        assert!(".".repeat(289).parse::<BigBox>().is_err());
+        assert!(PUZZLE[1..].parse::<BigBox>().is_err());
+        assert_eq!(".".repeat(16).parse::<BigBox>().unwrap().layout().size(), 4);
+    }
+
+    #[test]
+    fn grid_round_trip() {
+        let board: BigBox = PUZZLE.parse().unwrap();
+        let grid = board.to_grid(GridStyle::Ascii);
+        assert_eq!(grid.lines().count(), 13);
+        assert_eq!(grid.parse::<BigBox>().unwrap().to_string(), PUZZLE);
+
+        // bigger boards write letters past 9
+        let mut values = vec![0; 256];
+        values[0] = 16;
+        values[255] = 10;
+        let board = BigBox::with_layout(Layout::for_size(16).unwrap(), values).unwrap();
+        assert_eq!(
+            board
+                .to_grid(GridStyle::Ascii)
+                .parse::<BigBox>()
+                .unwrap()
+                .get_values(),
+            board.get_values()
+        );
+        assert!(board.to_grid(GridStyle::Unicode).contains('G'));
+    }
+
+    #[test]
+    fn pencilmark_round_trip() {
+        // a cell with one note would be read back as filled in, so only take
+        // a couple out. a cell that's run out of notes is written as a `.`
+        let mut board: BigBox = PUZZLE.parse().unwrap();
+        board.mark_impossible(vec![(1, 5), (1, 7), (3, 9)]);
+        board.little_boxes[0].make_all_impossible();
+
+        for style in [GridStyle::Ascii, GridStyle::Unicode].iter() {
+            let grid = board.to_pencilmark_grid(*style);
+            let read = BigBox::from_pencilmark_grid(&grid).unwrap();
+            assert_eq!(read.get_values(), board.get_values());
+            for (read, written) in read.little_boxes.iter().zip(&board.little_boxes) {
+                assert_eq!(read.get_possibles(), written.get_possibles());
+            }
+            assert!(read.little_boxes[0].get_possibles().is_empty());
+        }
+    }
+
+    #[test]
+    fn pencilmark_numbers_are_placed_not_given() {
+        let board: BigBox = PUZZLE.parse().unwrap();
+        let read =
+            BigBox::from_pencilmark_grid(&board.to_pencilmark_grid(GridStyle::Ascii)).unwrap();
+        assert_eq!(read.get_little_box_value(&2), Some(3));
+        assert!(!read.little_boxes[2].is_given());
+        assert!(read.get_givens().iter().all(|given| *given == 0));
+    }
+
+    #[test]
+    fn pencilmark_rows_of_empty_cells() {
+        let mut board: BigBox = PUZZLE.parse().unwrap();
+        for index in 0..9 {
+            board.little_boxes[index].update_value(0);
+            board.little_boxes[index].make_all_impossible();
+        }
+        let read =
+            BigBox::from_pencilmark_grid(&board.to_pencilmark_grid(GridStyle::Ascii)).unwrap();
+        assert!(read.little_boxes[..9]
+            .iter()
+            .all(|little_box| little_box.get_possibles().is_empty()));
+        assert_eq!(read.get_values()[9..], board.get_values()[9..]);
+    }
+}
@@ -0,0 +1,322 @@
+// ways to change a board that keep it a valid sudoku with the same number of
+// solutions: rotating, mirroring, relabeling the numbers and shuffling rows and
+// columns around without breaking up bands and stacks. notes move along with
+// their cells, so a half solved board stays half solved. turning a board with
+// boxes that aren't square turns the boxes too, a 6x6 board with 3 by 2 boxes
+// comes out with 2 by 3 boxes. a jigsaw's regions turn and flip along with the
+// cells, but rows and columns can't be shuffled without breaking them up, and
+// shuffling would break up an x-sudoku's diagonals or a killer's cages too, and
+// move cells that were a chess move apart on anti-knight and anti-king boards,
+// or take cells away from the markers between them and the lines through them.
+// the numbers can't be relabeled on boards where the rules depend on them, like
+// a killer's sums, kropki dots or thermometers.
+
+use crate::{BigBox, Layout, LittleBox};
+
+impl LittleBox {
+    // the same cell with its value and notes renamed, `mapping[n - 1]` is the new n
+    fn relabeled(&self, mapping: &[i32]) -> LittleBox {
+        let mut little_box = self.clone();
+        little_box.value = self.value.map(|value| match value {
+            value if (1..=self.size).contains(&value) => mapping[value as usize - 1],
+            _ => value,
+        });
+
+        let possibles: Vec<i32> = self
+            .get_possibles()
+            .iter()
+            .map(|possible| mapping[*possible as usize - 1])
+            .collect();
+        little_box.make_all_possible();
+        for number in 1..=self.size {
+            if !possibles.contains(&number) {
+                little_box.make_impossible(number);
+            }
+        }
+
+        little_box
+    }
+}
+
+impl BigBox {
+    // move every cell, `destination` takes the (row, col) of a cell and gives
+    // back where it ends up. `layout` is the shape of the board afterwards, a
+    // jigsaw's regions and the cages are moved here too
+    fn moved(&self, layout: Layout, destination: impl Fn(i32, i32) -> (i32, i32)) -> BigBox {
+        let size = self.layout.size();
+        let mut little_boxes = self.little_boxes.clone();
+        let mut moved_to = vec![0; little_boxes.len()];
+        for (index, little_box) in self.little_boxes.iter().enumerate() {
+            let (row, col) = destination(index as i32 / size, index as i32 % size);
+            let new_index = row * size + col;
+            little_boxes[new_index as usize] = LittleBox {
+                index: new_index,
+                ..little_box.clone()
+            };
+            moved_to[index] = new_index;
+        }
+
+        BigBox {
+            little_boxes,
+            times_updated: self.times_updated,
+            layout: layout.moved(&moved_to),
+        }
+    }
+
+    // the last row or column
+    fn last(&self) -> i32 {
+        self.layout.size() - 1
+    }
+
+    // rows and columns can only be shuffled when there's nothing but the rows,
+    // columns and boxes
+    fn can_shuffle(&self) -> bool {
+        !self.layout.is_jigsaw()
+            && self.layout.extra_units().is_empty()
+            && self.layout.cages().is_empty()
+            && !self.layout.has_anti_knight()
+            && !self.layout.has_anti_king()
+            && !self.layout.has_non_consecutive()
+            && !self.has_markers()
+            && self.layout.lines().is_empty()
+    }
+
+    // the numbers can only be renamed when the rules don't care what they are
+    fn can_relabel(&self) -> bool {
+        self.layout.cages().is_empty()
+            && !self.layout.has_non_consecutive()
+            && !self.has_markers()
+            && self.layout.lines().is_empty()
+    }
+
+    // kropki dots, Xs and Vs, or the negative constraints that go with them
+    fn has_markers(&self) -> bool {
+        !self.layout.markers().is_empty()
+            || self.layout.has_negative_kropki()
+            || self.layout.has_negative_xv()
+    }
+
+    // a quarter turn to the right
+    pub fn rotated_clockwise(&self) -> BigBox {
+        self.moved(self.layout.transposed(), |row, col| {
+            (col, self.last() - row)
+        })
+    }
+
+    // a quarter turn to the left
+    pub fn rotated_counter_clockwise(&self) -> BigBox {
+        self.moved(self.layout.transposed(), |row, col| {
+            (self.last() - col, row)
+        })
+    }
+
+    pub fn rotated_180(&self) -> BigBox {
+        self.moved(self.layout.clone(), |row, col| {
+            (self.last() - row, self.last() - col)
+        })
+    }
+
+    // flip top to bottom
+    pub fn reflected_horizontally(&self) -> BigBox {
+        self.moved(self.layout.clone(), |row, col| (self.last() - row, col))
+    }
+
+    // flip left to right
+    pub fn reflected_vertically(&self) -> BigBox {
+        self.moved(self.layout.clone(), |row, col| (row, self.last() - col))
+    }
+
+    // rows become columns, mirrored over the top left to bottom right diagonal
+    pub fn transposed(&self) -> BigBox {
+        self.moved(self.layout.transposed(), |row, col| (col, row))
+    }
+
+    // mirrored over the top right to bottom left diagonal
+    pub fn anti_transposed(&self) -> BigBox {
+        self.moved(self.layout.transposed(), |row, col| {
+            (self.last() - col, self.last() - row)
+        })
+    }
+
+    // rename the numbers, every n becomes `mapping[n - 1]`. None if the mapping
+    // isn't made of 1 to 9 (or however big the board is) each used once, or the
+    // board is a variant like a killer where that would break the rules
+    pub fn relabeled(&self, mapping: &[i32]) -> Option<BigBox> {
+        if !self.can_relabel()
+            || mapping.len() != self.layout.size() as usize
+            || self
+                .layout
+                .numbers()
+                .iter()
+                .any(|number| !mapping.contains(number))
+        {
+            return None;
+        }
+
+        Some(BigBox {
+            little_boxes: self
+                .little_boxes
+                .iter()
+                .map(|little_box| little_box.relabeled(mapping))
+                .collect(),
+            times_updated: self.times_updated,
+            layout: self.layout.clone(),
+        })
+    }
+
+    // swap two rows (0 to 8), None if they aren't in the same band or the
+    // board is a variant like a jigsaw (the same goes for the other swaps)
+    pub fn with_rows_swapped(&self, a: i32, b: i32) -> Option<BigBox> {
+        let band = self.layout.box_height();
+        if !self.can_shuffle()
+            || !(0..=self.last()).contains(&a)
+            || !(0..=self.last()).contains(&b)
+            || a / band != b / band
+        {
+            return None;
+        }
+        Some(self.moved(self.layout.clone(), |row, col| (swap(row, a, b), col)))
+    }
+
+    // swap two columns (0 to 8), None if they aren't in the same stack
+    pub fn with_columns_swapped(&self, a: i32, b: i32) -> Option<BigBox> {
+        let stack = self.layout.box_width();
+        if !self.can_shuffle()
+            || !(0..=self.last()).contains(&a)
+            || !(0..=self.last()).contains(&b)
+            || a / stack != b / stack
+        {
+            return None;
+        }
+        Some(self.moved(self.layout.clone(), |row, col| (row, swap(col, a, b))))
+    }
+
+    // swap two bands of 3 rows (0 to 2)
+    pub fn with_bands_swapped(&self, a: i32, b: i32) -> Option<BigBox> {
+        let band = self.layout.box_height();
+        let bands = self.layout.size() / band;
+        if !self.can_shuffle() || !(0..bands).contains(&a) || !(0..bands).contains(&b) {
+            return None;
+        }
+        Some(self.moved(self.layout.clone(), |row, col| {
+            (swap(row / band, a, b) * band + row % band, col)
+        }))
+    }
+
+    // swap two stacks of 3 columns (0 to 2)
+    pub fn with_stacks_swapped(&self, a: i32, b: i32) -> Option<BigBox> {
+        let stack = self.layout.box_width();
+        let stacks = self.layout.size() / stack;
+        if !self.can_shuffle() || !(0..stacks).contains(&a) || !(0..stacks).contains(&b) {
+            return None;
+        }
+        Some(self.moved(self.layout.clone(), |row, col| {
+            (row, swap(col / stack, a, b) * stack + col % stack)
+        }))
+    }
+}
+
+// a if it's b, b if it's a, otherwise the same number
+fn swap(number: i32, a: i32, b: i32) -> i32 {
+    if number == a {
+        b
+    } else if number == b {
+        a
+    } else {
+        number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::PUZZLE;
+    use crate::{count_solutions, Cage};
+
+    #[test]
+    fn turning_all_the_way_around() {
+        let board: BigBox = PUZZLE.parse().unwrap();
+        let turned = board.rotated_clockwise();
+        assert_eq!(
+            turned.get_little_box_value(&8),
+            board.get_little_box_value(&0)
+        );
+        assert_eq!(
+            turned.rotated_counter_clockwise().get_values(),
+            board.get_values()
+        );
+        assert_eq!(
+            turned.rotated_clockwise().get_values(),
+            board.rotated_180().get_values()
+        );
+        assert_eq!(
+            board.transposed().transposed().get_values(),
+            board.get_values()
+        );
+        assert_eq!(
+            board
+                .reflected_horizontally()
+                .reflected_vertically()
+                .get_values(),
+            board.rotated_180().get_values()
+        );
+        assert_eq!(
+            board.anti_transposed().get_values(),
+            board.transposed().rotated_180().get_values()
+        );
+    }
+
+    #[test]
+    fn transforms_keep_the_solution() {
+        let board: BigBox = PUZZLE.parse().unwrap();
+        let transformed = vec![
+            board.rotated_clockwise(),
+            board.anti_transposed(),
+            board.relabeled(&[2, 3, 4, 5, 6, 7, 8, 9, 1]).unwrap(),
+            board.with_rows_swapped(0, 2).unwrap(),
+            board.with_columns_swapped(4, 5).unwrap(),
+            board.with_bands_swapped(0, 2).unwrap(),
+            board.with_stacks_swapped(1, 2).unwrap(),
+        ];
+        for board in transformed {
+            assert_eq!(count_solutions(&board.get_values(), 2), 1);
+        }
+
+        // a 6x6 turns its boxes too
+        let board = BigBox::with_layout(Layout::new(3, 2).unwrap(), vec![0; 36]).unwrap();
+        let turned = board.rotated_clockwise();
+        assert_eq!(
+            (turned.layout().box_width(), turned.layout().box_height()),
+            (2, 3)
+        );
+    }
+
+    #[test]
+    fn bad_shuffles_are_none() {
+        let board: BigBox = PUZZLE.parse().unwrap();
+        assert!(board.with_rows_swapped(2, 3).is_none());
+        assert!(board.with_columns_swapped(0, 9).is_none());
+        assert!(board.with_bands_swapped(0, 3).is_none());
+        assert!(board.relabeled(&[1, 1, 2, 3, 4, 5, 6, 7, 8]).is_none());
+        assert!(board.relabeled(&[1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn variants_only_turn() {
+        let layout = Layout::standard()
+            .with_cages(vec![Cage::new(3, vec![0, 1])])
+            .unwrap();
+        let killer = BigBox::with_layout(layout, vec![0; 81]).unwrap();
+        assert!(killer.with_rows_swapped(0, 1).is_none());
+        assert!(killer.relabeled(&[9, 8, 7, 6, 5, 4, 3, 2, 1]).is_none());
+        assert_eq!(
+            killer.rotated_clockwise().layout().cages()[0].cells,
+            vec![8, 17]
+        );
+
+        let diagonal =
+            BigBox::with_layout(Layout::standard().with_diagonals(), vec![0; 81]).unwrap();
+        assert!(diagonal.with_stacks_swapped(0, 1).is_none());
+        assert!(diagonal.relabeled(&[9, 8, 7, 6, 5, 4, 3, 2, 1]).is_some());
+    }
+}